@@ -0,0 +1,189 @@
+use super::*;
+use num_integer::Integer as _;
+use num_traits::{One, Signed, Zero};
+
+// the longest period of sqrt(d) that is expanded. Periods grow like sqrt(d) log d, up to
+// about 2400 terms for d near 10^6, and pell keeps a convergent for each term.
+pub const MAX_PERIOD: usize = 1 << 12;
+
+// continued fractions: x = a_0 + 1 / (a_1 + 1 / (a_2 + ...)), written [a_0; a_1, a_2, ...].
+// a_0 is any integer, the other terms are positive.
+impl Rational {
+    // the finite expansion, by the Euclidean algorithm on numerator and denominator.
+    // Its last term is at least 2 unless it is a_0, which makes it unique.
+    pub fn contfrac(&self) -> Vec<Integer> {
+        let reduced = self.reduced();
+        let mut a = reduced.numerator;
+        let mut b = reduced.denominator;
+        let mut terms: Vec<Integer> = Vec::new();
+
+        while !b.is_zero() {
+            let (q, r) = a.div_mod_floor(&b);
+            terms.push(Integer::from_bigint(q));
+            a = b;
+            b = r;
+        }
+
+        terms
+    }
+
+    // the convergents p_k / q_k = [a_0; a_1, ..., a_k], by
+    // p_k = a_k p_(k-1) + p_(k-2) and q_k = a_k q_(k-1) + q_(k-2).
+    pub fn convergents(terms: &[Integer]) -> Vec<Rational> {
+        convergent_pairs(terms).into_iter()
+            .map(|(p, q)| Rational { numerator: p, denominator: q })
+            .collect()
+    }
+}
+
+// (p_k, q_k) for each k, as above. Both are coprime and q_k > 0.
+fn convergent_pairs(terms: &[Integer]) -> Vec<(BigInt, BigInt)> {
+    let (mut p_prev, mut p) = (BigInt::zero(), BigInt::one());
+    let (mut q_prev, mut q) = (BigInt::one(), BigInt::zero());
+    let mut pairs: Vec<(BigInt, BigInt)> = Vec::new();
+
+    for a in terms {
+        let p_next = &a.number * &p + &p_prev;
+        let q_next = &a.number * &q + &q_prev;
+        p_prev = p; p = p_next;
+        q_prev = q; q = q_next;
+        pairs.push((p.clone(), q.clone()));
+    }
+
+    pairs
+}
+
+impl Integer {
+    // sqrt(d) = [a_0; a_1, ..., a_r, a_1, ..., a_r, ...] for d > 0 not a square, where
+    // the period a_1, ..., a_r ends with a_r = 2 a_0. Returns Ok(Some((a_0, period))); Ok(None) otherwise.
+    // With sqrt(d) + m_k over d_k the k-th complete quotient, all of them are integers:
+    // m_(k+1) = d_k a_k - m_k, d_(k+1) = (d - m_(k+1)^2) / d_k, a_(k+1) = (a_0 + m_(k+1)) / d_(k+1).
+    //
+    // Err(MAX_PERIOD) if the period is longer than MAX_PERIOD.
+    pub fn sqrt_contfrac(&self) -> Result<Option<(Integer, Vec<Integer>)>, usize> {
+        let d = &self.number;
+        if !d.is_positive() {
+            return Ok(None);
+        }
+        let a_0 = d.sqrt();
+        if &a_0 * &a_0 == *d {
+            return Ok(None);
+        }
+
+        let mut period: Vec<Integer> = Vec::new();
+        let mut m = BigInt::zero();
+        let mut den = BigInt::one();
+        let mut a = a_0.clone();
+        let last = &a_0 * 2;
+        while a != last {
+            if period.len() == MAX_PERIOD {
+                return Err(MAX_PERIOD);
+            }
+            m = &den * &a - &m;
+            den = (d - &m * &m) / &den;
+            a = (&a_0 + &m) / &den;
+            period.push(Integer::from_bigint(a.clone()));
+        }
+
+        Ok(Some((Integer::from_bigint(a_0), period)))
+    }
+
+    // the fundamental solution (x, y) of x^2 - d y^2 = +-1, ie. the smallest with y > 0,
+    // along with the sign. It is the convergent [a_0; a_1, ..., a_(r-1)] of sqrt(d), and
+    // the sign is (-1)^r for r the length of the period. Ok(None) if d is not as in
+    // sqrt_contfrac, and Err(MAX_PERIOD) if the period is too long, as there.
+    pub fn pell(&self) -> Result<Option<(Integer, Integer, int)>, usize> {
+        let Some((a_0, period)) = self.sqrt_contfrac()? else {
+            return Ok(None);
+        };
+        let r = period.len();
+
+        let mut terms = vec![a_0];
+        terms.extend_from_slice(&period[..r - 1]);
+        let Some((x, y)) = convergent_pairs(&terms).pop() else {
+            return Ok(None);
+        };
+        let sign = if r.is_multiple_of(2) { 1 } else { -1 };
+
+        Ok(Some((Integer::from_bigint(x), Integer::from_bigint(y), sign)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(xs: &[int]) -> Vec<Integer> {
+        xs.iter().map(|&x| Integer::new(x)).collect()
+    }
+
+    #[test]
+    fn contfrac_of_rationals() {
+        let x = Rational { numerator: BigInt::from(355), denominator: BigInt::from(113) };
+        assert_eq!(x.contfrac(), terms(&[3, 7, 16]));
+
+        // a_0 is the floor, and a multiple of the denominator has no other terms.
+        let x = Rational { numerator: BigInt::from(-7), denominator: BigInt::from(3) };
+        assert_eq!(x.contfrac(), terms(&[-3, 1, 2]));
+        let x = Rational { numerator: BigInt::from(12), denominator: BigInt::from(-4) };
+        assert_eq!(x.contfrac(), terms(&[-3]));
+    }
+
+    #[test]
+    fn convergents_approach_the_value() {
+        let convergents = Rational::convergents(&terms(&[3, 7, 15, 1]));
+        let pairs: Vec<(BigInt, BigInt)> = convergents.into_iter()
+            .map(|c| (c.numerator, c.denominator))
+            .collect();
+        let expected = [(3, 1), (22, 7), (333, 106), (355, 113)];
+        assert_eq!(pairs, expected.map(|(p, q)| (BigInt::from(p), BigInt::from(q))));
+    }
+
+    #[test]
+    fn sqrt_contfrac_period() {
+        let (a_0, period) = Integer::new(13).sqrt_contfrac().unwrap().unwrap();
+        assert_eq!(a_0, Integer::new(3));
+        assert_eq!(period, terms(&[1, 1, 1, 1, 6]));
+
+        let (a_0, period) = Integer::new(2).sqrt_contfrac().unwrap().unwrap();
+        assert_eq!(a_0, Integer::new(1));
+        assert_eq!(period, terms(&[2]));
+
+        assert_eq!(Integer::new(16).sqrt_contfrac(), Ok(None));
+        assert_eq!(Integer::new(0).sqrt_contfrac(), Ok(None));
+        assert_eq!(Integer::new(-5).sqrt_contfrac(), Ok(None));
+    }
+
+    #[test]
+    fn pell_fundamental_solutions() {
+        // the period of sqrt(61) has length 11, so the first solution is of x^2 - 61 y^2 = -1.
+        let (x, y, sign) = Integer::new(61).pell().unwrap().unwrap();
+        assert_eq!((x, y, sign), (Integer::new(29718), Integer::new(3805), -1));
+
+        let (x, y, sign) = Integer::new(7).pell().unwrap().unwrap();
+        assert_eq!((x, y, sign), (Integer::new(8), Integer::new(3), 1));
+
+        let mut d = 2;
+        while d < 200 {
+            if let Ok(Some((x, y, sign))) = Integer::new(d).pell() {
+                let lhs = &x.number * &x.number - BigInt::from(d) * &y.number * &y.number;
+                assert_eq!(lhs, BigInt::from(sign));
+            }
+            d += 1;
+        }
+        assert_eq!(Integer::new(49).pell(), Ok(None));
+    }
+
+    #[test]
+    fn long_periods_are_refused() {
+        // the period of sqrt(1000861) has 2409 terms, that of sqrt(100000231) 21032.
+        let (_, period) = Integer::new(1000861).sqrt_contfrac().unwrap().unwrap();
+        assert_eq!(period.len(), 2409);
+        let (x, y, sign) = Integer::new(1000861).pell().unwrap().unwrap();
+        let lhs = &x.number * &x.number - BigInt::from(1000861) * &y.number * &y.number;
+        assert_eq!(lhs, BigInt::from(sign));
+
+        assert_eq!(Integer::new(100000231).sqrt_contfrac(), Err(MAX_PERIOD));
+        assert_eq!(Integer::new(100000231).pell(), Err(MAX_PERIOD));
+    }
+}
@@ -0,0 +1,1188 @@
+use super::*;
+use num_integer::Integer as _;
+use num_traits::{One, Signed, Zero};
+
+// x = unit * factors[0].0 ^ factors[0].1 * ... * factors[k].0 ^ factors[k].1.
+// The factors are irreducible, pairwise distinct, normalized (eg. monic) and sorted,
+// so that a factorization is canonical.
+#[derive(Clone, Debug)]
+pub struct Factorization<T> {
+    pub unit: T,
+    pub factors: Vec<(T, usize)>,
+}
+
+// a tiny xorshift generator. Cantor–Zassenhaus needs random polynomials, but not good
+// ones, and a fixed seed keeps the output of the calculator reproducible.
+pub(super) struct Xorshift {
+    state: u64,
+}
+
+impl Xorshift {
+    pub(super) fn new() -> Self {
+        Xorshift { state: 0x2545_f491_4f6c_dd1d }
+    }
+
+    pub(super) fn next(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    // a random integer in [0, n), n > 0. One spare word keeps the modulo bias negligible.
+    pub(super) fn below(&mut self, n: &Integer) -> Integer {
+        let words = n.number.bits() / 64 + 2;
+        let mut acc = BigInt::zero();
+        let mut i: u64 = 0;
+        while i < words {
+            acc = (acc << 64) + BigInt::from(self.next());
+            i += 1;
+        }
+
+        Integer::divmod(&Integer::from_bigint(acc), n).second
+    }
+}
+
+// order of ZPols in a factorization: by degree, then coefficientwise from the top.
+pub(super) fn cmp_zpol(f: &ZPol, g: &ZPol) -> std::cmp::Ordering {
+    let order = f.norm().cmp(&g.norm());
+    if order != std::cmp::Ordering::Equal {
+        return order;
+    }
+
+    let mut i = f.norm() + 1;
+    while i > 0 {
+        i -= 1;
+        let order = f.coefficient(i).cmp(&g.coefficient(i));
+        if order != std::cmp::Ordering::Equal {
+            return order;
+        }
+    }
+
+    std::cmp::Ordering::Equal
+}
+
+// Integer: primality and factorization. Trial division takes out the small primes,
+// Pollard's rho (with Brent's cycle detection) splits what is left.
+impl Integer {
+    pub fn is_prime(&self) -> bool {
+        prime::is_prime(self)
+    }
+
+    // whether is_prime is a proof for self rather than a probable answer.
+    pub fn primality_is_proven(&self) -> bool {
+        self.number.bits() <= prime::MAX_PROVEN_BITS
+    }
+
+    // the smallest prime strictly greater than self.
+    pub fn next_prime(&self) -> Integer {
+        let two = Integer::new(2);
+        if *self < two {
+            return two;
+        }
+
+        // start from the next odd number, and walk by steps of 2.
+        let mut candidate = Integer::add(self, &Integer::one());
+        if candidate.number.is_even() {
+            candidate = Integer::add(&candidate, &Integer::one());
+        }
+        while !candidate.is_prime() {
+            candidate = Integer::add(&candidate, &two);
+        }
+
+        candidate
+    }
+
+    // the Jacobi symbol (self/n), for n odd and positive; None otherwise. It is the product
+    // of the Legendre symbols (self/q) over the prime factors q of n, but computed by
+    // reciprocity, without factoring n.
+    pub fn jacobi(&self, n: &Integer) -> Option<int> {
+        let mut n = n.number.clone();
+        if !n.is_positive() || n.is_even() {
+            return None;
+        }
+
+        let mut a = self.number.mod_floor(&n);
+        let mut res: int = 1;
+        while !a.is_zero() {
+            // (2/n) = -1 exactly for n = 3, 5 mod 8.
+            while a.is_even() {
+                a >>= 1;
+                let r: BigInt = &n % 8u32;
+                if r == BigInt::from(3) || r == BigInt::from(5) {
+                    res = -res;
+                }
+            }
+            // (a/n) = -(n/a) exactly for a = n = 3 mod 4.
+            std::mem::swap(&mut a, &mut n);
+            if (&a % 4u32) == BigInt::from(3) && (&n % 4u32) == BigInt::from(3) {
+                res = -res;
+            }
+            a = a.mod_floor(&n);
+        }
+
+        if n.is_one() { Some(res) } else { Some(0) }
+    }
+
+    // Ok(None) if self is zero. Otherwise self = unit * product of positive primes,
+    // unit = +-1.
+    //
+    // Err(m) if m, a composite factor of self, could not be split within MAX_RHO_STEPS.
+    pub fn factor(&self) -> Result<Option<Factorization<Integer>>, Integer> {
+        if self.number.is_zero() {
+            return Ok(None);
+        }
+
+        let unit = if self.is_negative() { Integer::new(-1) } else { Integer::one() };
+        let mut rest = self.number.abs();
+        let mut primes: Vec<BigInt> = Vec::new();
+
+        for &q in &prime::SMALL_PRIMES {
+            let q = BigInt::from(q);
+            while (&rest % &q).is_zero() {
+                rest /= &q;
+                primes.push(q.clone());
+            }
+        }
+
+        let mut stack: Vec<BigInt> = Vec::new();
+        if !rest.is_one() {
+            stack.push(rest);
+        }
+        while let Some(n) = stack.pop() {
+            if prime::is_prime(&Integer::from_bigint(n.clone())) {
+                primes.push(n);
+                continue;
+            }
+            let Some(d) = pollard_rho(&n, MAX_RHO_STEPS) else {
+                return Err(Integer::from_bigint(n));
+            };
+            stack.push(&n / &d);
+            stack.push(d);
+        }
+
+        primes.sort();
+        let mut factors: Vec<(Integer, usize)> = Vec::new();
+        for q in primes {
+            match factors.last_mut() {
+                Some((last, e)) if last.number == q => *e += 1,
+                _ => factors.push((Integer::from_bigint(q), 1)),
+            }
+        }
+
+        Ok(Some(Factorization { unit, factors }))
+    }
+}
+
+// arithmetic functions of n > 0, read off the factorization n = product of q^e.
+// Each returns Ok(None) for n <= 0, and Err(m) if the factorization fails on m,
+// see Integer::factor.
+impl Integer {
+    // phi(n) = product of q^(e-1) (q - 1), the number of units mod n.
+    pub fn totient(&self) -> Result<Option<Integer>, Integer> {
+        let Some(factors) = self.positive_factors()? else {
+            return Ok(None);
+        };
+        let mut res = BigInt::one();
+        for (q, e) in factors {
+            res *= num_traits::pow(q.number.clone(), e - 1) * (&q.number - BigInt::one());
+        }
+
+        Ok(Some(Integer::from_bigint(res)))
+    }
+
+    // sigma(n), the sum of the divisors: product of (q^(e+1) - 1) / (q - 1).
+    pub fn sigma(&self) -> Result<Option<Integer>, Integer> {
+        let Some(factors) = self.positive_factors()? else {
+            return Ok(None);
+        };
+        let mut res = BigInt::one();
+        for (q, e) in factors {
+            let top: BigInt = num_traits::pow(q.number.clone(), e + 1) - BigInt::one();
+            res *= top / (&q.number - BigInt::one());
+        }
+
+        Ok(Some(Integer::from_bigint(res)))
+    }
+
+    // tau(n), the number of divisors: product of (e + 1).
+    pub fn num_divisors(&self) -> Result<Option<Integer>, Integer> {
+        let Some(factors) = self.positive_factors()? else {
+            return Ok(None);
+        };
+        let mut res = BigInt::one();
+        for (_, e) in factors {
+            res *= e + 1;
+        }
+
+        Ok(Some(Integer::from_bigint(res)))
+    }
+
+    // mu(n): 0 if a square divides n, else (-1)^k for k prime factors.
+    pub fn mobius(&self) -> Result<Option<int>, Integer> {
+        let Some(factors) = self.positive_factors()? else {
+            return Ok(None);
+        };
+        if factors.iter().any(|(_, e)| *e > 1) {
+            return Ok(Some(0));
+        }
+
+        Ok(Some(if factors.len().is_multiple_of(2) { 1 } else { -1 }))
+    }
+
+    // lambda(n), the exponent of the unit group mod n: the lcm of lambda(q^e), which is
+    // phi(q^e) except for 2^e with e >= 3, where the group is not cyclic and it is 2^(e-2).
+    pub fn carmichael(&self) -> Result<Option<Integer>, Integer> {
+        let Some(factors) = self.positive_factors()? else {
+            return Ok(None);
+        };
+        let mut res = BigInt::one();
+        for (q, e) in factors {
+            let lambda = if q.number == BigInt::from(2) && e >= 3 {
+                BigInt::one() << (e - 2)
+            } else {
+                num_traits::pow(q.number.clone(), e - 1) * (&q.number - BigInt::one())
+            };
+            res = res.lcm(&lambda);
+        }
+
+        Ok(Some(Integer::from_bigint(res)))
+    }
+
+    // the positive divisors of n, in increasing order.
+    pub fn divisors(&self) -> Result<Option<Vec<Integer>>, Integer> {
+        let Some(factors) = self.positive_factors()? else {
+            return Ok(None);
+        };
+        let mut divisors: Vec<BigInt> = vec![BigInt::one()];
+        for (q, e) in factors {
+            // each divisor so far, times q, q^2, ..., q^e.
+            let count = divisors.len();
+            let mut power = BigInt::one();
+            let mut k: usize = 0;
+            while k < e {
+                power *= &q.number;
+                let mut i: usize = 0;
+                while i < count {
+                    divisors.push(&divisors[i] * &power);
+                    i += 1;
+                }
+                k += 1;
+            }
+        }
+
+        divisors.sort();
+        Ok(Some(divisors.into_iter().map(Integer::from_bigint).collect()))
+    }
+
+    fn positive_factors(&self) -> Result<Option<Vec<(Integer, usize)>>, Integer> {
+        if !self.number.is_positive() {
+            return Ok(None);
+        }
+
+        Ok(self.factor()?.map(|factorization| factorization.factors))
+    }
+}
+
+// GaussInteger: factorization into Gaussian primes, from that of the norm over Z.
+// 2 = -i (1 + i)^2 ramifies, a prime p = 3 mod 4 stays prime, and a prime p = 1 mod 4
+// splits into two conjugates pi * conj(pi), with pi = gcd(p, x + i) for x^2 = -1 mod p.
+impl GaussInteger {
+    // Ok(None) if self is zero. Otherwise self = unit * product of regular Gaussian primes,
+    // unit in {1, i, -1, -i}.
+    //
+    // Err(m) if the factorization of the norm fails on m, see Integer::factor.
+    pub fn factor(&self) -> Result<Option<Factorization<GaussInteger>>, Integer> {
+        if self.is_zero() {
+            return Ok(None);
+        }
+
+        let a = &self.real.number; let b = &self.imag.number;
+        let norm = Integer::from_bigint(a * a + b * b);
+        let mut rest = self.clone();
+        let mut factors: Vec<(GaussInteger, usize)> = Vec::new();
+
+        // the norm is nonzero, and so is factored.
+        let Some(norm_factors) = norm.factor()? else {
+            return Ok(None);
+        };
+        for (p, _) in norm_factors.factors {
+            let residue = Integer::divmod(&p, &Integer::new(4)).second;
+            let candidates = if residue == Integer::new(2) {
+                vec![GaussInteger::new(1, 1)]
+            } else if residue == Integer::new(3) {
+                vec![GaussInteger::from_integers(p, Integer::zero())]
+            } else {
+                // -1 is a square mod p = 1 mod 4.
+                let Some(x) = Prime { p: p.clone() }.sqrt(&Integer::new(-1)) else {
+                    return Ok(None);
+                };
+                let pi = GaussInteger::bezout(&Duo {
+                    first: GaussInteger::from_integers(p, Integer::zero()),
+                    second: GaussInteger::from_integers(x, Integer::one()),
+                }).third;
+                let conj = GaussInteger::from_integers(pi.real.clone(), pi.imag.neg()).regular().second;
+                vec![pi, conj]
+            };
+
+            for pi in candidates {
+                let mut e: usize = 0;
+                loop {
+                    let duo = GaussInteger::divmod(&rest, &pi);
+                    if !duo.second.is_zero() {
+                        break;
+                    }
+                    rest = duo.first;
+                    e += 1;
+                }
+                if e > 0 {
+                    factors.push((pi, e));
+                }
+            }
+        }
+
+        factors.sort_by(|x, y| cmp_gauss(&x.0, &y.0));
+
+        Ok(Some(Factorization { unit: rest, factors }))
+    }
+}
+
+// order of Gaussian primes in a factorization: by norm, then by real part.
+fn cmp_gauss(x: &GaussInteger, y: &GaussInteger) -> std::cmp::Ordering {
+    let norm = |z: &GaussInteger| &z.real.number * &z.real.number + &z.imag.number * &z.imag.number;
+
+    norm(x).cmp(&norm(y)).then(x.real.cmp(&y.real))
+}
+
+// pollard_rho gives up after this many steps in total, which finds prime factors up to
+// about 2^40 within a second or two. Beyond it, a composite is left unsplit.
+pub const MAX_RHO_STEPS: u64 = 1 << 20;
+
+// a nontrivial divisor of n, which is composite, odd, and free of small prime factors.
+// Iterates x -> x^2 + c mod n, batching the gcds; a failed c is replaced by the next one.
+// None if no divisor turned up within max_steps steps.
+fn pollard_rho(n: &BigInt, max_steps: u64) -> Option<BigInt> {
+    let one = BigInt::one();
+    let mut c = BigInt::one();
+    let mut steps: u64 = 0;
+
+    loop {
+        let f = |x: &BigInt| (x * x + &c) % n;
+        let mut y = BigInt::from(2);
+        let mut x = y.clone();
+        let mut ys = y.clone();
+        let mut g = one.clone();
+        let mut q = one.clone();
+        let mut r: u64 = 1;
+        let batch: u64 = 128;
+
+        while g.is_one() {
+            if steps > max_steps {
+                return None;
+            }
+            x = y.clone();
+            let mut i: u64 = 0;
+            while i < r {
+                y = f(&y);
+                i += 1;
+            }
+
+            let mut k: u64 = 0;
+            while k < r && g.is_one() {
+                ys = y.clone();
+                let mut j: u64 = 0;
+                while j < batch.min(r - k) {
+                    y = f(&y);
+                    q = q * (&x - &y).abs() % n;
+                    j += 1;
+                }
+                g = q.gcd(n);
+                k += batch;
+            }
+            steps += 2 * r;
+            r *= 2;
+        }
+
+        // the batch overshot: walk it again one step at a time.
+        if g == *n {
+            loop {
+                ys = f(&ys);
+                g = (&x - &ys).abs().gcd(n);
+                if !g.is_one() {
+                    break;
+                }
+            }
+        }
+
+        if g != *n {
+            return Some(g);
+        }
+        c += 1;
+    }
+}
+
+// Prime acts on ZPol: factorization into monic irreducibles.
+// Square-free decomposition, then distinct-degree, then equal-degree (Cantor–Zassenhaus).
+impl Prime {
+    pub fn derivpol(&self, f: &ZPol) -> ZPol {
+        let red = self.modpol(f);
+        let ord = red.norm();
+        if ord == 0 {
+            return ZPol::zero();
+        }
+
+        let mut target: Vec<Z> = vec![Z::zero(); ord];
+        let mut i: usize = 1;
+        while i <= ord {
+            target[i - 1] = self.multiply(&red.vector[i], &Integer::new(i as int));
+            i += 1;
+        }
+
+        self.modpol(&ZPol { vector: target })
+    }
+
+    // the monic gcd, or zero if both are zero.
+    pub fn gcdpol(&self, f: &ZPol, g: &ZPol) -> ZPol {
+        let zero = ZPol::zero();
+        let mut a = self.modpol(f);
+        let mut b = self.modpol(g);
+
+        while b != zero {
+            let r = self.divmodpol(&a, &b).second;
+            a = b;
+            b = r;
+        }
+
+        self.regular(&a).second
+    }
+
+    // g with g(x)^p = f(x), for f with f' = 0, ie. f(x) = h(x^p).
+    // Over F(p) every coefficient is its own p-th root, so g = h.
+    fn pth_rootpol(&self, f: &ZPol) -> ZPol {
+        let red = self.modpol(f);
+        let ord = red.norm();
+        let p = self.p.norm();
+
+        let mut target: Vec<Z> = vec![Z::zero(); ord / p + 1];
+        let mut i: usize = 0;
+        while i * p <= ord {
+            target[i] = red.vector[i * p].clone();
+            i += 1;
+        }
+
+        ZPol { vector: target }.reduced()
+    }
+
+    // f monic. Returns pairs (g, e) of square-free, pairwise coprime, monic g of positive
+    // degree with f = product of g^e.
+    pub fn squarefree_decomposition(&self, f: &ZPol) -> Vec<(ZPol, usize)> {
+        let one = ZPol::one();
+        let mut res: Vec<(ZPol, usize)> = Vec::new();
+
+        // w is the product of the factors whose multiplicity is not divisible by p;
+        // each round peels off those of multiplicity exactly i.
+        let mut c = self.gcdpol(f, &self.derivpol(f));
+        let mut w = self.divmodpol(f, &c).first;
+        let mut i: usize = 1;
+
+        while w != one {
+            let y = self.gcdpol(&w, &c);
+            let fac = self.divmodpol(&w, &y).first;
+            if fac != one {
+                res.push((fac, i));
+            }
+            c = self.divmodpol(&c, &y).first;
+            w = y;
+            i += 1;
+        }
+
+        // what is left in c has multiplicities divisible by p.
+        if c != one {
+            let p = self.p.norm();
+            for (g, e) in self.squarefree_decomposition(&self.pth_rootpol(&c)) {
+                res.push((g, e * p));
+            }
+        }
+
+        res
+    }
+
+    // f monic and square-free. Returns pairs (g, d), where g is the product of all
+    // irreducible factors of f of degree d.
+    pub fn distinct_degree(&self, f: &ZPol) -> Vec<(ZPol, usize)> {
+        let one = ZPol::one();
+        let x = ZPol::monomial(&Z::one(), 1);
+        let mut res: Vec<(ZPol, usize)> = Vec::new();
+
+        let mut rest = f.clone();
+        // frob = x^(p^d) mod rest
+        let mut frob = self.divmodpol(&x, &rest).second;
+        let mut d: usize = 1;
+
+        while rest.norm() >= 2 * d {
+            frob = self.powmodpol(&frob, &self.p, &rest);
+            let g = self.gcdpol(&rest, &self.subpol(&frob, &x));
+            if g != one {
+                rest = self.divmodpol(&rest, &g).first;
+                frob = self.divmodpol(&frob, &rest).second;
+                res.push((g, d));
+            }
+            d += 1;
+        }
+
+        if rest != one {
+            let d = rest.norm();
+            res.push((rest, d));
+        }
+
+        res
+    }
+
+    // f monic, square-free, and a product of irreducibles of degree d. Returns them.
+    fn equal_degree(&self, f: &ZPol, d: usize, rng: &mut Xorshift) -> Vec<ZPol> {
+        let n = f.norm();
+        if n <= d {
+            return vec![f.clone()];
+        }
+
+        let two = Integer::new(2);
+        let q_d = Integer::from_bigint(num_traits::pow(self.p.number.clone(), d));
+        let half = Integer::divmod(&Integer::subtract(&q_d, &Integer::one()), &two).first;
+
+        loop {
+            let mut vec: Vec<Z> = Vec::new();
+            let mut i: usize = 0;
+            while i < n {
+                vec.push(rng.below(&self.p));
+                i += 1;
+            }
+            let a = ZPol { vector: vec }.reduced();
+
+            // modulo each factor, b is 0 for about half of them: for odd p, a^((p^d-1)/2)
+            // is a square root of 1; for p = 2 the trace a + a^2 + ... + a^(2^(d-1)) lies in F(2).
+            let b = if self.p == two {
+                let mut term = a.clone();
+                let mut trace = a;
+                let mut j: usize = 1;
+                while j < d {
+                    term = self.divmodpol(&self.mulpol(&term, &term), f).second;
+                    trace = self.addpol(&trace, &term);
+                    j += 1;
+                }
+                trace
+            } else {
+                self.subpol(&self.powmodpol(&a, &half, f), &ZPol::one())
+            };
+
+            let g = self.gcdpol(f, &b);
+            let k = g.norm();
+            if k > 0 && k < n {
+                let mut res = self.equal_degree(&g, d, rng);
+                res.extend(self.equal_degree(&self.divmodpol(f, &g).first, d, rng));
+                return res;
+            }
+        }
+    }
+
+    // None if f is zero. Otherwise f = unit * product of monic irreducibles.
+    pub fn factorpol(&self, f: &ZPol) -> Option<Factorization<ZPol>> {
+        let red = self.modpol(f);
+        if red == ZPol::zero() {
+            return None;
+        }
+
+        let reg = self.regular(&red);
+        let mut factors: Vec<(ZPol, usize)> = Vec::new();
+
+        if reg.second.norm() > 0 {
+            let mut rng = Xorshift::new();
+            for (sqf, e) in self.squarefree_decomposition(&reg.second) {
+                for (g, d) in self.distinct_degree(&sqf) {
+                    for h in self.equal_degree(&g, d, &mut rng) {
+                        factors.push((h, e));
+                    }
+                }
+            }
+        }
+
+        factors.sort_by(|x, y| cmp_zpol(&x.0, &y.0));
+
+        Some(Factorization {
+            unit: reg.first,
+            factors,
+        })
+    }
+}
+
+// QPol: factorization over Q, à la Zassenhaus. The primitive part of each square-free
+// part is factored mod a suitable prime p, the factors are Hensel-lifted to p^k,
+// beyond the Mignotte bound, and then recombined into true factors over Z.
+impl QPol {
+    pub fn derivative(&self) -> QPol {
+        let ord = self.norm();
+        if ord == 0 {
+            return QPol::zero();
+        }
+
+        let mut target: Vec<Rational> = vec![Rational::zero(); ord];
+        let mut i: usize = 1;
+        while i <= ord {
+            target[i - 1] = Rational::multiply(&self.vector[i], &Integer::new(i as int).to_rational());
+            i += 1;
+        }
+
+        QPol { vector: target }.reduced()
+    }
+
+    // f = product of a_i^i, a_i square-free, pairwise coprime and monic (Yun's algorithm).
+    // Only the a_i of positive degree are returned. f must be nonzero.
+    pub fn squarefree_decomposition(&self) -> Vec<(QPol, usize)> {
+        let one = QPol::one();
+        let mut res: Vec<(QPol, usize)> = Vec::new();
+        if self.norm() == 0 {
+            return res;
+        }
+
+        let f = self.regular().second;
+        let df = f.derivative();
+        let a = monic_gcd(&f, &df);
+        let mut b = QPol::divmod(&f, &a).first;
+        let c = QPol::divmod(&df, &a).first;
+        let mut d = QPol::subtract(&c, &b.derivative());
+        let mut i: usize = 1;
+
+        while b != one {
+            let a = monic_gcd(&b, &d);
+            b = QPol::divmod(&b, &a).first;
+            let c = QPol::divmod(&d, &a).first;
+            d = QPol::subtract(&c, &b.derivative());
+            if a != one {
+                res.push((a, i));
+            }
+            i += 1;
+        }
+
+        res
+    }
+
+    // None if self is zero. Otherwise self = unit * product of irreducible factors,
+    // each of them with coprime integer coefficients and a positive principal coefficient.
+    // The unit is a constant, ie. the rational content of self up to sign.
+    pub fn factor(&self) -> Option<Factorization<QPol>> {
+        if *self == QPol::zero() {
+            return None;
+        }
+
+        // the factors have integer principal coefficients, whose product is compensated
+        // in the unit.
+        let mut unit = self.principal_coefficient();
+        let mut factors: Vec<(ZPol, usize)> = Vec::new();
+
+        for (sqf, e) in self.squarefree_decomposition() {
+            for g in factor_squarefree_zpol(&sqf.primitive_part().1)? {
+                let lc = g.principal_coefficient().to_rational();
+                let mut k: usize = 0;
+                while k < e {
+                    unit = Rational::divide(&unit, &lc)?;
+                    k += 1;
+                }
+                factors.push((g, e));
+            }
+        }
+
+        factors.sort_by(|x, y| cmp_zpol(&x.0, &y.0));
+
+        Some(Factorization {
+            unit: QPol::as_polynomial(&unit),
+            factors: factors.into_iter().map(|(g, e)| (g.to_qpol(), e)).collect(),
+        })
+    }
+}
+
+fn monic_gcd(f: &QPol, g: &QPol) -> QPol {
+    let gcd = QPol::bezout(&Duo { first: f.clone(), second: g.clone() }).third;
+    gcd.regular().second
+}
+
+// f primitive, square-free, of positive degree, with a positive principal coefficient.
+// Returns its irreducible factors over Z, normalized the same way; None only if f is not
+// as above.
+fn factor_squarefree_zpol(f: &ZPol) -> Option<Vec<ZPol>> {
+    let n = f.norm();
+    if n == 1 {
+        return Some(vec![f.clone()]);
+    }
+
+    let (p, modular) = choose_prime(f)?;
+    if modular.len() == 1 {
+        return Some(vec![f.clone()]);
+    }
+
+    // any factor g of f satisfies |g|_inf <= 2^n |f|_1 (Mignotte), and recombination
+    // multiplies it by lc(f) at most. Lift until p^k exceeds twice that.
+    let mut norm_1 = Integer::zero();
+    for coeff in &f.vector {
+        norm_1 = Integer::add(&norm_1, &Integer::from_bigint(coeff.number.abs()));
+    }
+    let lc = f.principal_coefficient();
+    let bound = Integer::from_bigint(
+        (BigInt::from(2u32) << n) * &norm_1.number * lc.number.abs()
+    );
+
+    let mut modulus = p.to_integer();
+    while modulus <= bound {
+        modulus = Integer::multiply(&modulus, &p.to_integer());
+    }
+    let big_m = Modulus::try_from_integer(&modulus)?;
+
+    let lifted = hensel_lift(&p, &big_m, f, &modular)?;
+    Some(recombine(&big_m, f, lifted))
+}
+
+// the smallest (of a few) factorization of f mod p into monic irreducibles,
+// for primes p not dividing lc(f) such that f stays square-free mod p. f must be square-free.
+fn choose_prime(f: &ZPol) -> Option<(Prime, Vec<ZPol>)> {
+    let mut best: Option<(Prime, Vec<ZPol>)> = None;
+    let mut good: usize = 0;
+    let mut candidate: int = 2;
+
+    while good < 5 {
+        candidate += 1;
+        let Some(p) = Prime::try_new(candidate) else {
+            continue;
+        };
+        let red = p.modpol(f);
+        if red.norm() != f.norm() || p.gcdpol(&red, &p.derivpol(&red)) != ZPol::one() {
+            continue;
+        }
+
+        good += 1;
+        let Some(factorization) = p.factorpol(&red) else {
+            continue;
+        };
+        let modular: Vec<ZPol> = factorization.factors.into_iter().map(|(g, _)| g).collect();
+
+        let is_better = match &best {
+            None => true,
+            Some((_, prev)) => modular.len() < prev.len(),
+        };
+        if is_better {
+            best = Some((p, modular));
+        }
+    }
+
+    best
+}
+
+// f = lc(f) * g_1 * ... * g_r mod p, with monic g_i pairwise coprime mod p.
+// Returns monic h_i with f = lc(f) * h_1 * ... * h_r mod m, h_i = g_i mod p,
+// where m is a power of p. The factors are split off one by one; None if lc(f) is
+// divisible by p.
+fn hensel_lift(p: &Prime, m: &Modulus, f: &ZPol, modular: &[ZPol]) -> Option<Vec<ZPol>> {
+    let mut res: Vec<ZPol> = Vec::new();
+    let mut rest = m.modpol(f);
+    let r = modular.len();
+
+    let mut i: usize = 0;
+    while i + 1 < r {
+        let lc = ZPol::as_polynomial(&p.modulo(&rest.principal_coefficient()));
+        let mut cofactor = lc;
+        for g in &modular[i + 1..] {
+            cofactor = p.mulpol(&cofactor, g);
+        }
+
+        let (g, h) = lift_pair(p, m, &rest, &modular[i], &cofactor);
+        res.push(g);
+        rest = h;
+        i += 1;
+    }
+
+    // the last one is what is left, made monic.
+    let lc_inv = m.try_inv(&rest.principal_coefficient()).ok()?;
+    res.push(m.mulpol(&rest, &ZPol::as_polynomial(&lc_inv)));
+
+    Some(res)
+}
+
+// f = g * h mod p, g monic and coprime to h mod p. Lifts g and h one power of p
+// at a time, until f = g * h mod m, g still monic.
+fn lift_pair(p: &Prime, m: &Modulus, f: &ZPol, g: &ZPol, h: &ZPol) -> (ZPol, ZPol) {
+    let (s, t, _) = p.unwrapped_bezout(g, h);
+    let mut g = g.clone();
+    let mut h = h.clone();
+    let mut pj = p.to_integer();
+
+    while pj < m.to_integer() {
+        // f - g * h = pj * e. Find dg, dh with dg * h + dh * g = e mod p and deg dg < deg g:
+        // with s * g + t * h = 1, dg = t * e mod g, and dh = s * e + (t * e div g) * h.
+        let diff = ZPol::subtract(f, &ZPol::multiply(&g, &h));
+        let e = p.modpol(&ZPol {
+            vector: diff.vector.iter().map(|c| Integer::divmod(c, &pj).first).collect(),
+        });
+
+        let duo = p.divmodpol(&p.mulpol(&t, &e), &g);
+        let dg = duo.second;
+        let dh = p.addpol(&p.mulpol(&s, &e), &p.mulpol(&duo.first, &h));
+
+        let scale = ZPol::as_polynomial(&pj);
+        g = m.modpol(&ZPol::add(&g, &ZPol::multiply(&scale, &dg)));
+        h = m.modpol(&ZPol::add(&h, &ZPol::multiply(&scale, &dh)));
+        pj = Integer::multiply(&pj, &p.to_integer());
+    }
+
+    (g, h)
+}
+
+// the representative of f mod m with coefficients in (-m/2, m/2].
+fn symmetric(m: &Modulus, f: &ZPol) -> ZPol {
+    let half = Integer::divmod(&m.to_integer(), &Integer::new(2)).first;
+    ZPol {
+        vector: m.modpol(f).vector.iter().map(|c| {
+            if *c > half { Integer::subtract(c, &m.to_integer()) } else { c.clone() }
+        }).collect(),
+    }
+}
+
+// Some(f / g) if g divides f in Z[x].
+fn exact_quotient(f: &ZPol, g: &ZPol) -> Option<ZPol> {
+    let duo = QPol::divmod(&f.to_qpol(), &g.to_qpol());
+    if duo.second != QPol::zero() {
+        return None;
+    }
+
+    duo.first.to_zpol()
+}
+
+// Zassenhaus recombination: tries products of 1, 2, ... of the lifted factors,
+// scaled by the principal coefficient, as candidates for true factors of f.
+fn recombine(m: &Modulus, f: &ZPol, lifted: Vec<ZPol>) -> Vec<ZPol> {
+    let mut res: Vec<ZPol> = Vec::new();
+    let mut rest = f.clone();
+    let mut remaining = lifted;
+    let mut size: usize = 1;
+
+    while 2 * size <= remaining.len() {
+        let mut found: Option<(Vec<usize>, ZPol, ZPol)> = None;
+        let mut subset: Vec<usize> = (0..size).collect();
+
+        loop {
+            let lc = ZPol::as_polynomial(&rest.principal_coefficient());
+            let mut product = lc;
+            for &i in &subset {
+                product = m.mulpol(&product, &remaining[i]);
+            }
+            let candidate = symmetric(m, &product).primitive_part().1;
+
+            if let Some(quotient) = exact_quotient(&rest, &candidate) {
+                found = Some((subset.clone(), candidate, quotient));
+                break;
+            }
+            if !next_subset(&mut subset, remaining.len()) {
+                break;
+            }
+        }
+
+        match found {
+            Some((subset, factor, quotient)) => {
+                res.push(factor);
+                rest = quotient;
+                remaining = remaining.into_iter().enumerate()
+                    .filter(|(i, _)| !subset.contains(i))
+                    .map(|(_, g)| g)
+                    .collect();
+            }
+            None => size += 1,
+        }
+    }
+
+    if rest.norm() > 0 {
+        res.push(rest.primitive_part().1);
+    }
+
+    res
+}
+
+// advances an increasing list of indices below n to the next one, lexicographically.
+// false if it was the last one.
+fn next_subset(subset: &mut [usize], n: usize) -> bool {
+    let k = subset.len();
+    let mut i = k;
+    while i > 0 {
+        i -= 1;
+        if subset[i] < n - k + i {
+            subset[i] += 1;
+            let mut j = i + 1;
+            while j < k {
+                subset[j] = subset[j - 1] + 1;
+                j += 1;
+            }
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // coefficients from the constant term up.
+    fn zpol(coeffs: &[int]) -> ZPol {
+        ZPol { vector: coeffs.iter().map(|&c| Integer::new(c)).collect() }
+    }
+
+    fn expand_mod(p: &Prime, factorization: &Factorization<ZPol>) -> ZPol {
+        let mut res = factorization.unit.clone();
+        for (g, e) in &factorization.factors {
+            let mut k: usize = 0;
+            while k < *e {
+                res = p.mulpol(&res, g);
+                k += 1;
+            }
+        }
+
+        res
+    }
+
+    #[test]
+    fn factorpol_splits_into_linear_factors() {
+        let p = Prime::try_new(5).unwrap();
+        let f = zpol(&[-1, 0, 0, 0, 1]);
+        let res = p.factorpol(&f).unwrap();
+
+        assert_eq!(res.unit, ZPol::one());
+        assert_eq!(res.factors, vec![
+            (zpol(&[1, 1]), 1), (zpol(&[2, 1]), 1), (zpol(&[3, 1]), 1), (zpol(&[4, 1]), 1),
+        ]);
+    }
+
+    #[test]
+    fn factorpol_keeps_multiplicities_and_unit() {
+        // 2 (x + 1)^3 (x^2 + 1) over F(3), where (x + 1)^3 = x^3 + 1 needs a p-th root.
+        let p = Prime::try_new(3).unwrap();
+        let cube = p.mulpol(&p.mulpol(&zpol(&[1, 1]), &zpol(&[1, 1])), &zpol(&[1, 1]));
+        let f = p.mulpol(&zpol(&[2]), &p.mulpol(&cube, &zpol(&[1, 0, 1])));
+        let res = p.factorpol(&f).unwrap();
+
+        assert_eq!(res.unit, zpol(&[2]));
+        assert_eq!(res.factors, vec![(zpol(&[1, 1]), 3), (zpol(&[1, 0, 1]), 1)]);
+        assert_eq!(expand_mod(&p, &res), p.modpol(&f));
+    }
+
+    #[test]
+    fn factorpol_separates_degrees() {
+        // x^16 - x over F(2) is the product of the irreducibles of degree 1, 2 and 4.
+        let p = Prime::try_new(2).unwrap();
+        let mut coeffs = vec![0; 17];
+        coeffs[1] = 1;
+        coeffs[16] = 1;
+        let f = zpol(&coeffs);
+        let res = p.factorpol(&f).unwrap();
+
+        let degrees: Vec<usize> = res.factors.iter().map(|(g, _)| g.norm()).collect();
+        assert_eq!(degrees, vec![1, 1, 2, 4, 4, 4]);
+        assert!(res.factors.iter().all(|(g, e)| *e == 1 && p.is_irreducible(g)));
+        assert_eq!(expand_mod(&p, &res), f);
+    }
+
+    #[test]
+    fn factorpol_of_irreducible_and_zero() {
+        let p = Prime::try_new(7).unwrap();
+        let f = zpol(&[3, 0, 0, 1]);
+        let res = p.factorpol(&zpol(&[6, 0, 0, 2])).unwrap();
+
+        assert_eq!(res.unit, zpol(&[2]));
+        assert_eq!(res.factors, vec![(f, 1)]);
+        assert!(p.factorpol(&zpol(&[7, 14])).is_none());
+    }
+
+    #[test]
+    fn hensel_lift_keeps_factors_mod_p() {
+        // x^2 - 2 = (x + 3) (x + 4) mod 7, lifted to 7^4.
+        let p = Prime::try_new(7).unwrap();
+        let m = Modulus::try_new(2401).unwrap();
+        let f = zpol(&[-2, 0, 1]);
+        let modular = vec![zpol(&[3, 1]), zpol(&[4, 1])];
+        let lifted = hensel_lift(&p, &m, &f, &modular).unwrap();
+
+        assert_eq!(lifted.len(), 2);
+        assert_eq!(m.mulpol(&lifted[0], &lifted[1]), m.modpol(&f));
+        for (h, g) in lifted.iter().zip(&modular) {
+            assert_eq!(h.principal_coefficient(), Integer::one());
+            assert_eq!(p.modpol(h), *g);
+        }
+    }
+
+    #[test]
+    fn recombine_finds_true_factors() {
+        // (x^2 + 1) (x^2 - 2) splits into linear factors mod 17, and x^4 + 1 too,
+        // though it is irreducible over Z.
+        let p = Prime::try_new(17).unwrap();
+        let m = Modulus::try_new(17 * 17 * 17).unwrap();
+        for (f, expected) in [
+            (zpol(&[-2, 0, -1, 0, 1]), vec![zpol(&[-2, 0, 1]), zpol(&[1, 0, 1])]),
+            (zpol(&[1, 0, 0, 0, 1]), vec![zpol(&[1, 0, 0, 0, 1])]),
+        ] {
+            let modular: Vec<ZPol> = p.factorpol(&f).unwrap().factors.into_iter().map(|(g, _)| g).collect();
+            assert_eq!(modular.len(), 4);
+
+            let mut res = recombine(&m, &f, hensel_lift(&p, &m, &f, &modular).unwrap());
+            res.sort_by(cmp_zpol);
+            assert_eq!(res, expected);
+        }
+    }
+
+    #[test]
+    fn qpol_factor_normalizes_factors() {
+        // 6x^3 - 6x = 6 x (x - 1) (x + 1).
+        let res = zpol(&[0, -6, 0, 6]).to_qpol().factor().unwrap();
+        assert_eq!(res.unit, QPol::as_polynomial(&Rational::new(6, 1).unwrap()));
+        assert_eq!(res.factors, vec![
+            (zpol(&[-1, 1]).to_qpol(), 1), (zpol(&[0, 1]).to_qpol(), 1), (zpol(&[1, 1]).to_qpol(), 1),
+        ]);
+
+        // (x^2 + 1)^2 (x - 1/2) = 1/2 (2x - 1) (x^2 + 1)^2.
+        let square = ZPol::multiply(&zpol(&[1, 0, 1]), &zpol(&[1, 0, 1])).to_qpol();
+        let half = QPol::as_polynomial(&Rational::new(1, 2).unwrap());
+        let f = QPol::multiply(&square, &QPol::subtract(&zpol(&[0, 1]).to_qpol(), &half));
+        let res = f.factor().unwrap();
+        assert_eq!(res.unit, half);
+        assert_eq!(res.factors, vec![(zpol(&[-1, 2]).to_qpol(), 1), (zpol(&[1, 0, 1]).to_qpol(), 2)]);
+
+        assert!(QPol::zero().factor().is_none());
+    }
+
+    #[test]
+    fn qpol_factor_of_irreducible_quartic() {
+        let f = zpol(&[1, 0, 0, 0, 1]).to_qpol();
+        let res = f.factor().unwrap();
+
+        assert_eq!(res.unit, QPol::one());
+        assert_eq!(res.factors, vec![(f, 1)]);
+    }
+
+    fn expand(factorization: &Factorization<Integer>) -> Integer {
+        let mut res = factorization.unit.clone();
+        for (q, e) in &factorization.factors {
+            let mut k: usize = 0;
+            while k < *e {
+                res = Integer::multiply(&res, q);
+                k += 1;
+            }
+        }
+
+        res
+    }
+
+    #[test]
+    fn integer_factor_small() {
+        let res = Integer::new(-360).factor().unwrap().unwrap();
+        assert_eq!(res.unit, Integer::new(-1));
+        assert_eq!(res.factors, vec![(Integer::new(2), 3), (Integer::new(3), 2), (Integer::new(5), 1)]);
+
+        assert!(Integer::new(1).factor().unwrap().unwrap().factors.is_empty());
+        assert_eq!(Integer::zero().factor().map(|x| x.is_none()), Ok(true));
+    }
+
+    #[test]
+    fn integer_factor_by_pollard_rho() {
+        // two primes beyond trial division, one of them squared.
+        let n = Integer::from_bigint(BigInt::from(8) * BigInt::from(1000000007) * BigInt::from(998244353).pow(2));
+        let res = n.factor().unwrap().unwrap();
+
+        assert_eq!(res.factors, vec![
+            (Integer::new(2), 3), (Integer::new(998244353), 2), (Integer::new(1000000007), 1),
+        ]);
+        assert_eq!(expand(&res), n);
+    }
+
+    #[test]
+    fn pollard_rho_gives_up() {
+        // 1000003 * 1000033 needs about 1000 steps, (2^50 - 27)(2^50 - 35) about 2^25.
+        let n = BigInt::from(1000003) * BigInt::from(1000033);
+        let d = pollard_rho(&n, 1 << 12).unwrap();
+        assert!(d == BigInt::from(1000003) || d == BigInt::from(1000033));
+
+        let p = (BigInt::one() << 50u32) - BigInt::from(27);
+        let q = (BigInt::one() << 50u32) - BigInt::from(35);
+        assert_eq!(pollard_rho(&(p * q), 1 << 12), None);
+    }
+
+    #[test]
+    fn integer_factor_of_large_prime() {
+        let p = Integer::from_bigint((BigInt::one() << 89u32) - BigInt::one());
+        let res = p.factor().unwrap().unwrap();
+
+        assert_eq!(res.factors, vec![(p, 1)]);
+    }
+
+    fn expand_gauss(factorization: &Factorization<GaussInteger>) -> GaussInteger {
+        let mut res = factorization.unit.clone();
+        for (pi, e) in &factorization.factors {
+            let mut k: usize = 0;
+            while k < *e {
+                res = GaussInteger::multiply(&res, pi);
+                k += 1;
+            }
+        }
+
+        res
+    }
+
+    #[test]
+    fn gauss_factor_by_residue_mod_4() {
+        // 2 ramifies, 5 = 1 mod 4 splits and 3 = 3 mod 4 stays prime.
+        let res = GaussInteger::new(2, 0).factor().unwrap().unwrap();
+        assert_eq!(res.unit, GaussInteger::new(0, -1));
+        assert_eq!(res.factors, vec![(GaussInteger::new(1, 1), 2)]);
+
+        let res = GaussInteger::new(5, 0).factor().unwrap().unwrap();
+        assert_eq!(res.unit, GaussInteger::new(0, -1));
+        assert_eq!(res.factors, vec![(GaussInteger::new(1, 2), 1), (GaussInteger::new(2, 1), 1)]);
+
+        let res = GaussInteger::new(0, -3).factor().unwrap().unwrap();
+        assert_eq!(res.unit, GaussInteger::new(0, -1));
+        assert_eq!(res.factors, vec![(GaussInteger::new(3, 0), 1)]);
+
+        assert_eq!(GaussInteger::new(0, 0).factor().map(|x| x.is_none()), Ok(true));
+    }
+
+    #[test]
+    fn gauss_factor_multiplies_back() {
+        // i (2 + i)^3 (1 + i) 3 (4 + 9i), where 97 = 4^2 + 9^2 is prime.
+        let mut z = GaussInteger::new(0, 1);
+        for factor in [(2, 1), (2, 1), (2, 1), (1, 1), (3, 0), (4, 9)] {
+            z = GaussInteger::multiply(&z, &GaussInteger::new(factor.0, factor.1));
+        }
+        let res = z.factor().unwrap().unwrap();
+
+        let exponents: Vec<usize> = res.factors.iter().map(|(_, e)| *e).collect();
+        assert_eq!(exponents, vec![1, 3, 1, 1]);
+        assert_eq!(expand_gauss(&res), z);
+    }
+
+    #[test]
+    fn arithmetic_functions() {
+        let n = Integer::new(360);
+        assert_eq!(n.totient(), Ok(Some(Integer::new(96))));
+        assert_eq!(n.sigma(), Ok(Some(Integer::new(1170))));
+        assert_eq!(n.num_divisors(), Ok(Some(Integer::new(24))));
+        assert_eq!(n.mobius(), Ok(Some(0)));
+        assert_eq!(Integer::new(30).mobius(), Ok(Some(-1)));
+        assert_eq!(Integer::new(561).carmichael(), Ok(Some(Integer::new(80))));
+
+        let divisors = n.divisors().unwrap().unwrap();
+        assert_eq!(divisors.len(), 24);
+        assert!(divisors.windows(2).all(|pair| pair[0] < pair[1]));
+
+        for n in [0, -12] {
+            let n = Integer::new(n);
+            assert_eq!(n.totient(), Ok(None));
+            assert_eq!(n.num_divisors(), Ok(None));
+            assert_eq!(n.divisors(), Ok(None));
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use super::*;
+use super::factor::Xorshift;
+
+// the search for a modulus of GF(p^n) tests about n candidates, each with Ben-Or's test
+// in up to n^3 log2(p) operations mod p, which get slower once p exceeds a machine word.
+// The degree is capped to keep this within a few seconds, see GaloisField::max_degree.
+pub const MAX_FIELD_COST: u64 = 1 << 22;
+
+// the number of candidates tried in lexicographic order before the random search,
+// and the degree below which the random candidates may have nonzero coefficients.
+const LEXICOGRAPHIC_CANDIDATES: usize = 256;
+const SPARSE_DEGREE: usize = 32;
+
+// the finite field GF(p^n) = F(p)[x]/(f), where f is a monic irreducible polynomial of
+// degree n. Elements are ZPols reduced mod (p, f), ie. of degree < n.
+#[derive(Clone, Debug)]
+pub struct GaloisField {
+    pub p: Prime,
+    pub modulus: ZPol,
+}
+
+impl GaloisField {
+    // None if the modulus is not irreducible over F(p). It is made monic otherwise.
+    pub fn try_new(p: &Prime, modulus: &ZPol) -> Option<Self> {
+        if !p.is_irreducible(modulus) {
+            return None;
+        }
+
+        Some(GaloisField {
+            p: p.clone(),
+            modulus: p.regular(modulus).second,
+        })
+    }
+
+    // GF(p^n) with an automatically chosen modulus, see search_irreducible.
+    // n must be positive, and at most max_degree(p) to finish in reasonable time.
+    pub fn with_degree(p: &Prime, n: usize) -> Self {
+        GaloisField {
+            p: p.clone(),
+            modulus: Self::search_irreducible(p, n),
+        }
+    }
+
+    // the largest n with n^3 log2(p) w <= MAX_FIELD_COST, where p takes w machine words,
+    // and at least 1: the largest degree whose moduli can be searched for, or tested for
+    // irreducibility, within the budget.
+    pub fn max_degree(p: &Prime) -> usize {
+        let bits = p.p.number.bits();
+        let cost = bits * (bits / 64 + 1);
+        let mut n: u64 = 1;
+        while (n + 1).pow(3) * cost <= MAX_FIELD_COST {
+            n += 1;
+        }
+
+        n as usize
+    }
+
+    // a monic irreducible polynomial of degree n over F(p). The first candidates in
+    // lexicographic order, from x^(n-1) down to the constant term, are tried first, so that
+    // small fields get a short modulus, eg. x^2 + 1 over F(3), x^3 + x + 1 over F(2).
+    // Then candidates are drawn at random, about 1 in n of them being irreducible;
+    // the fixed seed of Xorshift keeps the choice reproducible.
+    //
+    // For small fields it often coincides with the Conway polynomial, but not always:
+    // Conway polynomials also require x to be a primitive element, and compatibility
+    // between subfields, which is a lot more expensive to search for.
+    pub fn search_irreducible(p: &Prime, n: usize) -> ZPol {
+        let zero = Z::zero();
+        let mut vec: Vec<Z> = vec![zero.clone(); n + 1];
+        vec[n] = Z::one();
+
+        let mut count: usize = 0;
+        while count < LEXICOGRAPHIC_CANDIDATES {
+            let candidate = ZPol { vector: vec.clone() };
+            if p.is_irreducible(&candidate) {
+                return candidate;
+            }
+
+            // count up in base p, the constant term being the lowest digit.
+            let mut i: usize = 0;
+            while i < n {
+                vec[i] = p.add(&vec[i], &Z::one());
+                if vec[i] != zero {
+                    break;
+                }
+                i += 1;
+            }
+            count += 1;
+        }
+
+        // the random candidates are x^n + g, with g of degree below SPARSE_DEGREE, whose
+        // reduction is cheap. They are still irreducible about 1 in n times.
+        let mut rng = Xorshift::new();
+        let low = n.min(SPARSE_DEGREE);
+        loop {
+            let mut i: usize = 0;
+            while i < low {
+                vec[i] = rng.below(&p.p);
+                i += 1;
+            }
+
+            let candidate = ZPol { vector: vec.clone() };
+            if p.is_irreducible(&candidate) {
+                return candidate;
+            }
+        }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.modulus.norm()
+    }
+
+    pub fn reduce(&self, f: &ZPol) -> ZPol {
+        self.p.divmodpol(f, &self.modulus).second
+    }
+}
+
+// arithmetic of the field. Outputs are always reduced.
+impl GaloisField {
+    pub fn add(&self, f: &ZPol, g: &ZPol) -> ZPol {
+        self.reduce(&self.p.addpol(f, g))
+    }
+
+    pub fn subtract(&self, f: &ZPol, g: &ZPol) -> ZPol {
+        self.reduce(&self.p.subpol(f, g))
+    }
+
+    pub fn neg(&self, f: &ZPol) -> ZPol {
+        self.reduce(&self.p.negpol(f))
+    }
+
+    pub fn multiply(&self, f: &ZPol, g: &ZPol) -> ZPol {
+        self.reduce(&self.p.mulpol(f, g))
+    }
+
+    pub fn equal(&self, f: &ZPol, g: &ZPol) -> bool {
+        self.reduce(f) == self.reduce(g)
+    }
+
+    // None if f = 0 in the field. Since the modulus is irreducible, every other
+    // element is coprime to it and bezout gives U * f + V * modulus = 1.
+    pub fn try_inv(&self, f: &ZPol) -> Option<ZPol> {
+        let red = self.reduce(f);
+        if red == ZPol::zero() {
+            return None;
+        }
+
+        let (u, _, _) = self.p.unwrapped_bezout(&red, &self.modulus);
+
+        Some(self.reduce(&u))
+    }
+
+    // None if g = 0 in the field.
+    pub fn divide(&self, f: &ZPol, g: &ZPol) -> Option<ZPol> {
+        let inv_g = self.try_inv(g)?;
+
+        Some(self.multiply(f, &inv_g))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zpol(coeffs: &[int]) -> ZPol {
+        ZPol { vector: coeffs.iter().map(|&c| Integer::new(c)).collect() }
+    }
+
+    #[test]
+    fn default_modulus_is_irreducible() {
+        for (p, n) in [(2, 1), (2, 8), (3, 2), (5, 7), (101, 3), (7, 40)] {
+            let p = Prime::try_new(p).unwrap();
+            let modulus = GaloisField::with_degree(&p, n).modulus;
+            assert_eq!(modulus.norm(), n);
+            assert_eq!(modulus.principal_coefficient(), Integer::one());
+            assert_eq!(p.factorpol(&modulus).unwrap().factors, vec![(modulus.clone(), 1)]);
+        }
+
+        // small fields get the first modulus in lexicographic order.
+        let f3 = Prime::try_new(3).unwrap();
+        assert_eq!(GaloisField::with_degree(&f3, 2).modulus, zpol(&[1, 0, 1]));
+        let f2 = Prime::try_new(2).unwrap();
+        assert_eq!(GaloisField::with_degree(&f2, 8).modulus, zpol(&[1, 1, 0, 1, 1, 0, 0, 0, 1]));
+    }
+
+    #[test]
+    fn large_characteristic() {
+        // the lexicographic search would have to go through p^4 candidates here.
+        let p = Prime::try_new(1000000007).unwrap();
+        let field = GaloisField::with_degree(&p, 5);
+        assert_eq!(field.degree(), 5);
+        assert_eq!(p.factorpol(&field.modulus).unwrap().factors.len(), 1);
+
+        let x = ZPol::monomial(&Integer::one(), 1);
+        let inv = field.try_inv(&x).unwrap();
+        assert_eq!(field.multiply(&x, &inv), ZPol::one());
+    }
+
+    #[test]
+    fn large_degree_over_f2() {
+        let p = Prime::try_new(2).unwrap();
+        assert_eq!(GaloisField::max_degree(&p), 128);
+
+        // x^128 + x^7 + x^2 + x + 1, the modulus of GCM.
+        let mut coeffs = vec![0; 129];
+        for i in [0, 1, 2, 7, 128] {
+            coeffs[i] = 1;
+        }
+        assert_eq!(GaloisField::with_degree(&p, 128).modulus, zpol(&coeffs));
+
+        // beyond the first candidates in lexicographic order.
+        let modulus = GaloisField::with_degree(&p, 85).modulus;
+        assert_eq!(modulus.norm(), 85);
+        assert!(p.is_irreducible(&modulus));
+    }
+}
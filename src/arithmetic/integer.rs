@@ -1,28 +1,34 @@
 use super::*;
+use num_integer::Integer as _;
+use num_traits::{Signed, ToPrimitive, One, Zero};
 
-#[derive(Clone, Copy, Debug)] 
+#[derive(Clone, Debug)] 
 pub struct Integer{
-    pub number: int,
+    pub number: BigInt,
 }
 
 impl Integer{
     pub fn new(x: int) -> Integer {
+        Integer{number: BigInt::from(x)}
+    }
+
+    pub fn from_bigint(x: BigInt) -> Integer {
         Integer{number: x}
     }
 
     pub fn to_rational(&self) -> Rational {
         Rational {
-            numerator: self.number,
-            denominator: 1
+            numerator: self.number.clone(),
+            denominator: BigInt::one()
         }
     }
 
     pub fn is_positive(&self) -> bool {
-        self.number > 0
+        self.number.is_positive()
     }
 
     pub fn is_negative(&self) -> bool {
-        self.number < 0
+        self.number.is_negative()
     }
 }
 
@@ -36,33 +42,38 @@ impl EuclideanRing for Integer{
     }
 
     fn add (x: &Self, y:& Self) -> Self{
-        Self::new(x.number + y.number)
+        Self::from_bigint(&x.number + &y.number)
     }
     
     fn subtract (x: &Self, y:& Self) -> Self{
-        Self::new(x.number - y.number)
+        Self::from_bigint(&x.number - &y.number)
     }
 
     fn multiply (x: &Self, y:& Self) -> Self{
-        Self::new(x.number * y.number)
+        Self::from_bigint(&x.number * &y.number)
     }
 
     fn neg(&self) -> Self{
-        Self::new(-self.number)
+        Self::from_bigint(-&self.number)
     }
 
-    fn divmod(x: &Self, y: &Self) -> Duo<Self> {
+    // same convention as i64::div_euclid / rem_euclid: the remainder is never negative.
+    fn try_divmod(x: &Self, y: &Self) -> Option<Duo<Self>> {
         if Integer::equal(y, &Integer::zero()) {
-            panic!("cannot divide by zero")
-        }
-        Duo::<Self>{
-            first: Self::new(x.number.div_euclid(y.number)), 
-            second: Self::new(x.number.rem_euclid(y.number)),
+            return None;
         }
+        let r = x.number.mod_floor(&y.number.abs());
+        let q = (&x.number - &r) / &y.number;
+
+        Some(Duo::<Self>{
+            first: Self::from_bigint(q), 
+            second: Self::from_bigint(r),
+        })
     }
 
+    // saturates at usize::MAX for huge numbers.
     fn norm(&self) -> usize {
-        self.number.abs() as usize
+        self.number.magnitude().to_usize().unwrap_or(usize::MAX)
     }
 
     fn equal(x: &Self, y:&Self) -> bool {
@@ -70,22 +81,22 @@ impl EuclideanRing for Integer{
     }
 
     fn regular(&self) -> Trio<Self> {
-        if self.number < 0 {
-            return Trio::<Self> {
+        if self.number.is_negative() {
+            Trio::<Self> {
                 first: Self::new(-1),
                 second: self.neg(),
                 third: Self::new(-1),
             }
         }
-        else if self.number == 0 {
-            return Trio::<Self> {
+        else if self.number.is_zero() {
+            Trio::<Self> {
                 first: Self::new(0),
                 second: Self::new(0),
                 third: Self::new(0),
             }
         }
         else {
-            return Trio::<Self> {
+            Trio::<Self> {
                 first: Self::new(1),
                 second: self.clone(),
                 third: Self::new(1),
@@ -95,7 +106,7 @@ impl EuclideanRing for Integer{
 }
 
 
-#[derive(Clone, Copy, Debug)] 
+#[derive(Clone, Debug)] 
 pub struct GaussInteger{
     pub real: Integer,
     pub imag: Integer,
@@ -106,6 +117,10 @@ impl GaussInteger{
         GaussInteger { real: Integer::new(real), imag: Integer::new(imag) }
     }
 
+    pub fn from_integers(real: Integer, imag: Integer) -> Self {
+        GaussInteger { real, imag }
+    }
+
     pub fn to_complex_rational(&self) -> ComplexRational {
         ComplexRational {
             real: self.real.to_rational(),
@@ -133,68 +148,69 @@ impl EuclideanRing for GaussInteger{
     }
 
     fn add (x: &Self, y:& Self) -> Self {
-        Self::new(x.real.number + y.real.number, x.imag.number + y.imag.number)
+        Self::from_integers(Integer::add(&x.real, &y.real), Integer::add(&x.imag, &y.imag))
     }
 
     fn subtract (x: &Self, y:& Self) -> Self {
-        Self::new(x.real.number - y.real.number, x.imag.number - y.imag.number)
-        
+        Self::from_integers(Integer::subtract(&x.real, &y.real), Integer::subtract(&x.imag, &y.imag))
     }
 
     // (a+bi)*(c+di) = (ac-bd) + (bc+ad) i 
     fn multiply (x: &Self, y:& Self) -> Self{
-        let a = x.real.number; let b = x.imag.number;
-        let c = y.real.number; let d = y.imag.number;
+        let a = &x.real.number; let b = &x.imag.number;
+        let c = &y.real.number; let d = &y.imag.number;
 
-        Self::new(a*c-b*d, b*c+a*d)
+        Self::from_integers(
+            Integer::from_bigint(a*c-b*d),
+            Integer::from_bigint(b*c+a*d)
+        )
     }
 
     fn neg(&self) -> Self {
-        Self::new(-self.real.number, -self.imag.number)
+        Self::from_integers(self.real.neg(), self.imag.neg())
     }
 
     fn equal(x: &Self, y: &Self) -> bool {
         (x.real.number == y.real.number) && (x.imag.number == y.imag.number)
     }
 
+    // saturates at usize::MAX for huge numbers.
     fn norm(&self) -> usize {
-        (self.real.number * self.real.number + self.imag.number * self.imag.number) as usize
+        let a = &self.real.number; let b = &self.imag.number;
+        (a*a + b*b).to_usize().unwrap_or(usize::MAX)
     }
     
-    fn divmod(x: &Self, y: &Self) -> Duo<Self> {
-        if y.is_zero() {
-            panic!("Cannot divide by zero.");
-        }
-        
+    fn try_divmod(x: &Self, y: &Self) -> Option<Duo<Self>> {
         let x_rat = x.to_complex_rational(); let y_rat = y.to_complex_rational();
-        let q = (x_rat/y_rat).nearest_gauss_integer();
-        let r = *x - (q * *y);
+        let q = ComplexRational::divide(&x_rat, &y_rat)?.nearest_gauss_integer();
+        let r = Self::subtract(x, &Self::multiply(&q, y));
 
-        Duo::<Self> {
+        Some(Duo::<Self> {
             first: q, second: r
-        }
+        })
     }
 
     fn regular(&self) -> Trio<Self> {
         let i = Self::new(0,1);
         let one = Self::one();
         let z = self.clone();
+        let zi = Self::multiply(&z, &i);
 
         if z.is_regular() {
-            return Trio::<Self> {
-                first: one, second: z, third: one
+            Trio::<Self> {
+                first: one.clone(), second: z, third: one
             }
-        } else if (z * i).is_regular() {
-            return Trio::<Self> {
-                first: -i, second: z * i, third: i
+        } else if zi.is_regular() {
+            Trio::<Self> {
+                first: i.neg(), second: zi, third: i
             }
-        } else if (-z).is_regular() {
-            return Trio::<Self> {
-                first: -one, second: -z, third: -one
+        } else if z.neg().is_regular() {
+            Trio::<Self> {
+                first: one.neg(), second: z.neg(), third: one.neg()
             }
         } else {
-            return Trio::<Self> {
-                first: i, second: -(z * i), third: -i
+            Trio::<Self> {
+                first: i.clone(), second: zi.neg(), third: i.neg()
             }
         }
     }
@@ -214,16 +230,7 @@ mod chore {
 
     impl std::cmp::Ord for Integer {
         fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-            use std::cmp::Ordering;
-
-            let res = Integer::subtract(self, other);
-            if res.number < 0 {
-                return Ordering::Less;
-            } else if res.number > 0 {
-                return Ordering::Greater;
-            } else {
-                return Ordering::Equal;
-            }
+            self.number.cmp(&other.number)
         }
     }
 
@@ -298,7 +305,7 @@ mod chore {
         }
     }
 
-        impl std::ops::Neg for GaussInteger {
+    impl std::ops::Neg for GaussInteger {
         type Output = Self;
 
         fn neg(self) -> Self {
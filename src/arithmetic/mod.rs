@@ -8,12 +8,27 @@ mod polynomial;
 pub use polynomial::{Polynomial, Z, ZPol, QPol};
 
 mod prime;
-pub use prime::Prime;
+pub use prime::{Prime, MAX_DLOG_FACTOR, MAX_PROVEN_BITS};
+
+mod modulus;
+pub use modulus::Modulus;
+
+mod galois;
+pub use galois::{GaloisField, MAX_FIELD_COST};
+
+mod factor;
+pub use factor::{Factorization, MAX_RHO_STEPS};
+
+mod contfrac;
+pub use contfrac::MAX_PERIOD;
 
 mod ring;
-pub use ring::{Field, EuclideanRing, Ring};
+pub use ring::{Field, EuclideanRing, EuclideanDomain, RingOf, EuclidStep, LinearSolution, Ring};
 
 pub use i64 as int;
 
+// arbitrary-precision backend of Integer, Rational and everything built on them.
+pub use num_bigint::BigInt;
+
 
-use crate::{Duo, Trio};
\ No newline at end of file
+use crate::{Duo, Trio};
@@ -0,0 +1,189 @@
+use super::*;
+use num_traits::Signed;
+
+// the ring Z/nZ for an arbitrary modulus n >= 2. Compared with Prime, division is
+// only partially defined: x is invertible iff gcd(x, n) = 1.
+#[derive(Clone)]
+pub struct Modulus {
+    pub n: Integer,
+}
+
+impl Modulus {
+    pub fn try_new(input: int) -> Option<Self> {
+        if input < 2 {
+            return None;
+        }
+
+        Some(Modulus {n: Integer::new(input)})
+    }
+
+    pub fn try_from_integer(input: &Integer) -> Option<Self> {
+        if *input < Integer::new(2) {
+            return None;
+        }
+
+        Some(Modulus {n: input.clone()})
+    }
+
+    pub fn to_integer(&self) -> Integer {
+        self.n.clone()
+    }
+}
+
+// Modulus acts on Integer. Outputs always fall in range [0, n-1].
+impl Modulus {
+    pub fn modulo(&self, x: &Integer) -> Integer {
+        Integer::divmod(x, &self.n).second
+    }
+
+    pub fn add(&self, x: &Integer, y: &Integer) -> Integer {
+        let res = Integer::add(x, y);
+        self.modulo(&res)
+    }
+
+    pub fn neg(&self, x: &Integer) -> Integer {
+        let res = x.neg();
+        self.modulo(&res)
+    }
+
+    pub fn subtract(&self, x: &Integer, y: &Integer) -> Integer {
+        let res = Integer::subtract(x, y);
+        self.modulo(&res)
+    }
+
+    pub fn multiply(&self, x: &Integer, y: &Integer) -> Integer {
+        let res = Integer::multiply(x, y);
+        self.modulo(&res)
+    }
+
+    pub fn equal(&self, x: &Integer, y: &Integer) -> bool {
+        self.modulo(x) == self.modulo(y)
+    }
+
+    // Ok(inverse) if x is a unit. Otherwise Err(gcd(x, n)), which is the obstruction.
+    pub fn try_inv(&self, x: &Integer) -> Result<Integer, Integer> {
+        let red = self.modulo(x);
+        let (u, _, gcd) = Integer::unwrapped_bezout(&red, &self.n);
+
+        if !gcd.is_one() {
+            return Err(gcd);
+        }
+
+        Ok(self.modulo(&u))
+    }
+
+    // same convention as try_inv.
+    pub fn divide(&self, x: &Integer, y: &Integer) -> Result<Integer, Integer> {
+        let inv_y = self.try_inv(y)?;
+
+        Ok(self.multiply(x, &inv_y))
+    }
+
+    // x^e by fast exponentiation. A negative e raises the inverse of x,
+    // so it fails as try_inv does when x is not a unit.
+    pub fn pow(&self, x: &Integer, e: &Integer) -> Result<Integer, Integer> {
+        let base = if e.number.is_negative() { self.try_inv(x)? } else { self.modulo(x) };
+        let res = base.number.modpow(&e.number.abs(), &self.n.number);
+
+        Ok(Integer::from_bigint(res))
+    }
+
+    pub fn is_unit(&self, x: &Integer) -> bool {
+        self.try_inv(x).is_ok()
+    }
+
+    // a nonzero x is a zero divisor iff d = gcd(x, n) > 1, and then x * (n/d) = 0.
+    // Some(n/d) as witness if x is a zero divisor; None if x is a unit or zero.
+    pub fn zero_divisor_witness(&self, x: &Integer) -> Option<Integer> {
+        let red = self.modulo(x);
+        if red.is_zero() {
+            return None;
+        }
+
+        let gcd = self.try_inv(&red).err()?;
+
+        Some(Integer::divmod(&self.n, &gcd).first)
+    }
+
+    pub fn is_zero_divisor(&self, x: &Integer) -> bool {
+        self.zero_divisor_witness(x).is_some()
+    }
+}
+
+// Modulus acts on ZPol. (Z/nZ)[x] is not Euclidean in general, so there is no bezout;
+// division works as long as the leading coefficient of the divisor is a unit.
+impl Modulus {
+    pub fn modpol(&self, f: &ZPol) -> ZPol {
+        let ord = f.norm();
+        let vec = f.reduced().vector;
+        let mut target = vec.clone();
+
+        let mut i: usize = 0;
+        while i <= ord {
+            target[i] = self.modulo(&vec[i]);
+            i += 1;
+        }
+
+        ZPol { vector: target }.reduced()
+    }
+
+    pub fn addpol(&self, f: &ZPol, g: &ZPol) -> ZPol {
+        let res = ZPol::add(f, g);
+        self.modpol(&res)
+    }
+
+    pub fn subpol(&self, f: &ZPol, g: &ZPol) -> ZPol {
+        let res = ZPol::subtract(f, g);
+        self.modpol(&res)
+    }
+
+    pub fn mulpol(&self, f: &ZPol, g: &ZPol) -> ZPol {
+        let res = ZPol::multiply(f, g);
+        self.modpol(&res)
+    }
+
+    pub fn negpol(&self, f: &ZPol) -> ZPol {
+        let res = ZPol::neg(f);
+        self.modpol(&res)
+    }
+
+    pub fn eqpol(&self, f: &ZPol, g: &ZPol) -> bool {
+        self.modpol(f) == self.modpol(g)
+    }
+
+    // Err(gcd) if the principal coefficient of g is not a unit, see try_inv.
+    // g must be nonzero.
+    pub fn divmodpol(&self, f: &ZPol, g: &ZPol) -> Result<Duo<ZPol>, Integer> {
+        let zero = ZPol::zero();
+
+        let mut dividend = self.modpol(f);
+        let mut dividend_order = dividend.norm();
+        let divisor = self.modpol(g);
+
+        let divisor_order = divisor.norm();
+        let divisor_inv = self.try_inv(&divisor.principal_coefficient())?;
+
+        if dividend_order < divisor_order || dividend == zero {
+            return Ok(Duo::<ZPol>{
+                first: ZPol::zero(),
+                second: dividend,
+            })
+        }
+
+        let mut final_quotient =
+            ZPol::monomial(&Z::zero(), dividend_order - divisor_order);
+
+        while dividend_order >= divisor_order && dividend != zero {
+            let temp_coeff = self.multiply(&dividend.principal_coefficient(), &divisor_inv);
+            let temp_q = ZPol::monomial(&temp_coeff, dividend_order - divisor_order);
+            dividend = self.subpol(&dividend, &self.mulpol(&temp_q, &divisor));
+            dividend_order = dividend.norm();
+            final_quotient = self.addpol(&final_quotient, &temp_q);
+        }
+
+        Ok(Duo::<ZPol>{
+            first: final_quotient,
+            second: dividend,
+        })
+    }
+}
@@ -31,7 +31,7 @@ impl<T: Field> Polynomial<T>{
 
         let mut i: usize = 0;
         while i <= k {
-            target[i] = vec[i];
+            target[i] = vec[i].clone();
             i += 1;
         }
         Polynomial::<T>{vector: target}
@@ -40,7 +40,7 @@ impl<T: Field> Polynomial<T>{
     // read coefficient at the given term.
     pub fn coefficient(&self, order: usize) -> T {
         let vec = &self.vector;
-        vec[order]
+        vec[order].clone()
     }
 
     // read coefficient at highest term. if the pol is non-zero, then it's principal coeff
@@ -95,7 +95,7 @@ impl<T: Field> EuclideanRing for Polynomial<T>{
             i += 1;
         }
         while i <= higher_order {
-            target[i] = longer_vec[i];
+            target[i] = longer_vec[i].clone();
             i += 1;
         }
 
@@ -151,11 +151,11 @@ impl<T: Field> EuclideanRing for Polynomial<T>{
     fn subtract (x: &Self, y:& Self) -> Self {
         Self::add(x, &y.neg())
     }
-    fn divmod(x: &Self, y:&Self) -> Duo<Self> {
+    fn try_divmod(x: &Self, y:&Self) -> Option<Duo<Self>> {
         let zero = Self::zero();
 
         if *y == zero {
-            panic!("cannot divide by zero")
+            return None;
         }
         
         let mut dividend = x.reduced();
@@ -163,30 +163,30 @@ impl<T: Field> EuclideanRing for Polynomial<T>{
         let divisor = y.clone();
         
         let divisor_order = y.norm();
-        let divisor_coefficient = divisor.vector[divisor_order];
+        let divisor_coefficient = divisor.vector[divisor_order].clone();
 
         if dividend_order < divisor_order || dividend == zero {
-            return Duo::<Self>{
+            return Some(Duo::<Self>{
                 first: Self::zero(),
                 second: dividend,
-            }
+            })
         }
 
         let mut final_quotient = 
             Self::monomial(&T::zero(), dividend_order - divisor_order);
         
         while dividend_order >= divisor_order && dividend != zero {
-            let temp_coeff = T::divide(&dividend.principal_coefficient(), &divisor_coefficient);
+            let temp_coeff = T::divide(&dividend.principal_coefficient(), &divisor_coefficient)?;
             let temp_q = Self::monomial(&temp_coeff, dividend_order - divisor_order);
             dividend = Self::subtract(&dividend, &Self::multiply(&temp_q, &divisor));
             dividend_order = dividend.norm();
             final_quotient = Self::add(&final_quotient, &temp_q);
         }
 
-        Duo::<Self>{
+        Some(Duo::<Self>{
             first: final_quotient,
             second: dividend,
-        }
+        })
     }
 
     fn regular(&self) -> Trio<Self> {
@@ -198,8 +198,15 @@ impl<T: Field> EuclideanRing for Polynomial<T>{
             }
         }
 
+        // a nonzero polynomial has a nonzero principal coefficient.
         let pr_coeff = self.principal_coefficient();
-        let monicify_coeff = pr_coeff.inv();
+        let Some(monicify_coeff) = pr_coeff.inv() else {
+            return Trio::<Self> {
+                first: Self::zero(),
+                second: Self::zero(),
+                third: Self::zero(),
+            }
+        };
         let inv_sign = Self::as_polynomial(&monicify_coeff);
 
         let sign = Self::as_polynomial(&pr_coeff);
@@ -225,7 +232,7 @@ impl<T: Field> std::cmp::PartialEq for Polynomial<T> {
 // special pol type with integer coefficients. It can be converted from QPol if possible.
 // only find its use in serving as a demonstrator for a  PrimePol.
 // it may be made generic in the future, but I don't see a need for that right now.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct ZPol {
     pub vector: Vec<Z>,
 }
@@ -251,7 +258,7 @@ impl ZPol {
 
         let mut i: usize = 0;
         while i <= k {
-            target[i] = vec[i];
+            target[i] = vec[i].clone();
             i += 1;
         }
         
@@ -260,7 +267,7 @@ impl ZPol {
 
     pub fn coefficient(&self, order: usize) -> Z {
         let vec = &self.vector;
-        vec[order]
+        vec[order].clone()
     }
 
     pub fn principal_coefficient(&self) -> Z {
@@ -318,7 +325,7 @@ impl ZPol {
             i += 1;
         }
         while i <= higher_order {
-            target[i] = longer_vec[i];
+            target[i] = longer_vec[i].clone();
             i += 1;
         }
 
@@ -416,11 +423,7 @@ impl QPol {
 
         let mut i: usize = 0;
         while i <= ord {
-            let Some(int) = vec[i].to_integer() else {
-                return None;
-            };
-
-            target[i] = int;
+            target[i] = vec[i].to_integer()?;
 
             i += 1;
         }
@@ -431,6 +434,59 @@ impl QPol {
     }
 }
 
+// content and primitive part: self = c * F, where c is a rational number and F has
+// coprime integer coefficients with a positive principal coefficient.
+// The zero polynomial gives (0, 0).
+impl QPol {
+    pub fn primitive_part(&self) -> (Rational, ZPol) {
+        let red = QPol { vector: self.reduced().vector.iter().map(Rational::reduced).collect() };
+        if red == QPol::zero() {
+            return (Rational::zero(), ZPol::zero());
+        }
+
+        let mut lcm = Integer::one();
+        for coeff in &red.vector {
+            let den = Integer::from_bigint(coeff.denominator.clone());
+            let gcd = Integer::gcd(&Duo { first: lcm.clone(), second: den.clone() });
+            lcm = Integer::divmod(&Integer::multiply(&lcm, &den), &gcd).first;
+        }
+
+        // each denominator divides lcm.
+        let target: Vec<Z> = red.vector.iter().map(|coeff| {
+            let scaled = &coeff.numerator * (&lcm.number / &coeff.denominator);
+            Integer::from_bigint(scaled)
+        }).collect();
+
+        let (content, prim) = ZPol { vector: target }.primitive_part();
+
+        (Rational { numerator: content.number, denominator: lcm.number }.reduced(), prim)
+    }
+}
+
+impl ZPol {
+    // self = c * F with c an integer, see QPol::primitive_part.
+    pub fn primitive_part(&self) -> (Integer, ZPol) {
+        let red = self.reduced();
+        if red == ZPol::zero() {
+            return (Integer::zero(), ZPol::zero());
+        }
+
+        let mut gcd = Integer::zero();
+        for coeff in &red.vector {
+            gcd = Integer::bezout(&Duo { first: gcd, second: coeff.clone() }).third;
+        }
+        if red.principal_coefficient().is_negative() {
+            gcd = gcd.neg();
+        }
+
+        let target: Vec<Z> = red.vector.iter()
+            .map(|coeff| Integer::divmod(coeff, &gcd).first)
+            .collect();
+
+        (gcd, ZPol { vector: target })
+    }
+}
+
 // important: ZPol -> QPol conversion, for re-use of qpol diplayer
 impl ZPol {
     pub fn to_qpol(&self) -> QPol {
@@ -1,42 +1,169 @@
 use super::*;
+use num_integer::Integer as _;
+use num_traits::{One, Signed, Zero};
+use std::collections::HashMap;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Debug)]
 pub struct Prime {
-    pub p: int,
+    pub p: Integer,
 }
 
-pub fn is_prime(input: int) -> bool {
-    let k = input;
-    if k <= 1 {
+// small primes, used for trial division and as Miller–Rabin bases.
+pub(super) const SMALL_PRIMES: [u32; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41,
+    43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+// the largest prime factor q of the order that dlog accepts. Baby-step giant-step keeps
+// a table of sqrt(q) powers, 2^18 at most.
+pub const MAX_DLOG_FACTOR: u64 = 1 << 36;
+
+// numbers below 2^81, a little under 3.3 * 10^24, are proven prime by is_prime.
+pub const MAX_PROVEN_BITS: u64 = 81;
+
+// Miller–Rabin. With the first 13 primes as bases, the test is deterministic below
+// 3.3 * 10^24, which covers every int. Above that, it is Baillie–PSW: Miller–Rabin
+// to the base 2 and a strong Lucas test. No composite passing both is known, though
+// they are believed to exist, so the answer is only "probably prime" there.
+pub fn is_prime(input: &Integer) -> bool {
+    let n = &input.number;
+    if *n < BigInt::from(2) {
         return false;
     }
-    let mut i = 2;
-    while i < k {
-        if (k % i) == 0 {return false;}
+    for &q in &SMALL_PRIMES {
+        if *n == BigInt::from(q) {
+            return true;
+        }
+        if (n % q).is_zero() {
+            return false;
+        }
+    }
+
+    if n.bits() <= MAX_PROVEN_BITS {
+        return SMALL_PRIMES[..13].iter().all(|&a| is_strong_probable_prime(n, a));
+    }
+
+    is_strong_probable_prime(n, 2) && is_strong_lucas_probable_prime(n)
+}
+
+// one round of Miller–Rabin, n odd and coprime to a.
+fn is_strong_probable_prime(n: &BigInt, a: u32) -> bool {
+    // n - 1 = d * 2^s with d odd.
+    let n_minus_1: BigInt = n - BigInt::one();
+    let Some(s) = n_minus_1.trailing_zeros() else {
+        return false;
+    };
+    let d = &n_minus_1 >> s;
+
+    let mut x = BigInt::from(a).modpow(&d, n);
+    if x.is_one() || x == n_minus_1 {
+        return true;
+    }
+    let mut i: u64 = 1;
+    while i < s {
+        x = &x * &x % n;
+        if x == n_minus_1 {
+            return true;
+        }
         i += 1;
     }
-    true
+
+    false
+}
+
+// the strong Lucas test, n odd and free of small factors, with Selfridge's parameters:
+// D the first of 5, -7, 9, -11, ... with (D/n) = -1, P = 1 and Q = (1 - D) / 4.
+// With n + 1 = d * 2^s, d odd, a prime n has U_d = 0 or V_(d 2^r) = 0 for some r < s.
+fn is_strong_lucas_probable_prime(n: &BigInt) -> bool {
+    // no D exists for a square.
+    let root = n.sqrt();
+    if &root * &root == *n {
+        return false;
+    }
+
+    let n_int = Integer::from_bigint(n.clone());
+    let mut d = BigInt::from(5);
+    loop {
+        match Integer::from_bigint(d.clone()).jacobi(&n_int) {
+            Some(-1) => break,
+            // a common factor, unless it is n itself.
+            Some(0) if d.abs() != *n => return false,
+            _ => {}
+        }
+        let step = BigInt::from(2);
+        d = if d.is_positive() { -(d + step) } else { -d + step };
+    }
+    let q: BigInt = (BigInt::one() - &d) / 4;
+
+    // halves mod n, n odd.
+    let half = |x: BigInt| -> BigInt {
+        let x = if x.is_odd() { x + n } else { x };
+        (x >> 1u32).mod_floor(n)
+    };
+
+    let n_plus_1: BigInt = n + BigInt::one();
+    let Some(s) = n_plus_1.trailing_zeros() else {
+        return false;
+    };
+    let k = &n_plus_1 >> s;
+
+    // (U_j, V_j, Q^j) for j the leading bits of k, by U_2j = U_j V_j, V_2j = V_j^2 - 2 Q^j,
+    // U_(j+1) = (P U_j + V_j) / 2 and V_(j+1) = (D U_j + P V_j) / 2.
+    let mut u = BigInt::one();
+    let mut v = BigInt::one();
+    let mut q_j = q.mod_floor(n);
+    let mut i = k.bits() - 1;
+    while i > 0 {
+        i -= 1;
+        u = (&u * &v).mod_floor(n);
+        v = (&v * &v - &q_j * 2u32).mod_floor(n);
+        q_j = (&q_j * &q_j).mod_floor(n);
+        if k.bit(i) {
+            let u_next = half(&u + &v);
+            v = half(&d * &u + &v);
+            u = u_next;
+            q_j = (&q_j * &q).mod_floor(n);
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    let mut r: u64 = 1;
+    while r < s {
+        v = (&v * &v - &q_j * 2u32).mod_floor(n);
+        if v.is_zero() {
+            return true;
+        }
+        q_j = (&q_j * &q_j).mod_floor(n);
+        r += 1;
+    }
+
+    false
 }
 
 impl Prime {
     pub fn try_new(input: int) -> Option<Self>{
+        Self::try_from_integer(&Integer::new(input))
+    }
+
+    pub fn try_from_integer(input: &Integer) -> Option<Self>{
         if !is_prime(input) {
             return None;
         }
 
-        Some(Prime {p: input})
+        Some(Prime {p: input.clone()})
     }
 
     pub fn to_integer(&self) -> Integer{
-        Integer::new(self.p)
+        self.p.clone()
     }
 }
 
 // Prime acts on Integer. Outputs always fall in range [0, p-1].
 impl Prime {
     pub fn modulo(&self, n: &Integer) -> Integer {
-        let x = n.number.rem_euclid(self.p);
-        Integer::new(x)
+        Integer::divmod(n, &self.p).second
     }
 
     pub fn add(&self, x: &Integer, y: &Integer) -> Integer {
@@ -59,20 +186,22 @@ impl Prime {
         self.modulo(&res)
     }
 
-    pub fn inv(&self, input: &Integer) -> Integer {
+    // None if input is divisible by p.
+    pub fn try_inv(&self, input: &Integer) -> Option<Integer> {
         let x = self.modulo(input);
-        if x.number == 0 { panic!(); }
+        if x.is_zero() { return None; }
 
         let p = self.to_integer();
         let (u, _, _) = Integer::unwrapped_bezout(&x, &p);
 
-        self.modulo(&u)
+        Some(self.modulo(&u))
     }
 
-    pub fn divide(&self, x: &Integer, y: &Integer) -> Integer {
-        let inv_y = self.inv(y);
-        
-        self.multiply(x, &inv_y)
+    // None if y is divisible by p.
+    pub fn divide(&self, x: &Integer, y: &Integer) -> Option<Integer> {
+        let inv_y = self.try_inv(y)?;
+
+        Some(self.multiply(x, &inv_y))
     }
 
     pub fn equal(&self, x: &Z, y: &Z) -> bool {
@@ -80,6 +209,235 @@ impl Prime {
     }
 }
 
+// the multiplicative group F(p)*, cyclic of order p - 1.
+impl Prime {
+    // x^e by fast exponentiation. A negative e raises the inverse of x;
+    // None if x is divisible by p then.
+    pub fn pow(&self, x: &Integer, e: &Integer) -> Option<Integer> {
+        let base = if e.number.is_negative() { self.try_inv(x)? } else { self.modulo(x) };
+        let res = base.number.modpow(&e.number.abs(), &self.p.number);
+
+        Some(Integer::from_bigint(res))
+    }
+
+    // the prime factors of p - 1, each once. The group functions below need them, and
+    // return Err(m) like Integer::factor if they cannot be found.
+    fn group_factors(&self) -> Result<Vec<Integer>, Integer> {
+        let order = Integer::subtract(&self.p, &Integer::one());
+        // p - 1 >= 1 is nonzero, so this always factors.
+        let Some(factorization) = order.factor()? else {
+            return Ok(Vec::new());
+        };
+
+        Ok(factorization.factors.into_iter().map(|(q, _)| q).collect())
+    }
+
+    // the smallest k > 0 with x^k = 1, a divisor of p - 1. Ok(None) if x is divisible by p.
+    // Starting from p - 1, each prime factor q is divided out while x^(k/q) is still 1.
+    pub fn order(&self, x: &Integer) -> Result<Option<Integer>, Integer> {
+        if self.modulo(x).is_zero() {
+            return Ok(None);
+        }
+
+        let mut k = Integer::subtract(&self.p, &Integer::one());
+        for q in self.group_factors()? {
+            while Integer::divmod(&k, &q).second.is_zero() {
+                let smaller = Integer::divmod(&k, &q).first;
+                if self.pow(x, &smaller) != Some(Integer::one()) {
+                    break;
+                }
+                k = smaller;
+            }
+        }
+
+        Ok(Some(k))
+    }
+
+    // whether x generates F(p)*, ie. x^((p-1)/q) != 1 for every prime q dividing p - 1.
+    pub fn is_primroot(&self, x: &Integer) -> Result<bool, Integer> {
+        Ok(self.is_generator(x, &self.group_factors()?))
+    }
+
+    // the smallest primitive root in [1, p-1].
+    pub fn primroot(&self) -> Result<Integer, Integer> {
+        let factors = self.group_factors()?;
+        let mut g = Integer::one();
+        while !self.is_generator(&g, &factors) {
+            g = Integer::add(&g, &Integer::one());
+        }
+
+        Ok(g)
+    }
+
+    // the Legendre symbol (x/p) by Euler's criterion: x^((p-1)/2) is 1 for a nonzero square,
+    // -1 for a non-square, and 0 for zero. None for p = 2, where the symbol is undefined.
+    pub fn legendre(&self, x: &Integer) -> Option<int> {
+        let two = BigInt::from(2);
+        if self.p.number == two {
+            return None;
+        }
+
+        let half: BigInt = (&self.p.number - BigInt::one()) / &two;
+        let y = self.modulo(x).number.modpow(&half, &self.p.number);
+        if y.is_zero() {
+            Some(0)
+        } else if y.is_one() {
+            Some(1)
+        } else {
+            Some(-1)
+        }
+    }
+
+    // r with r^2 = x, by Tonelli–Shanks; the other root is p - r. None if x is no square.
+    // Of the two roots, the smaller one is returned.
+    pub fn sqrt(&self, x: &Integer) -> Option<Integer> {
+        let n = &self.p.number;
+        let a = self.modulo(x).number;
+        if a.is_zero() || *n == BigInt::from(2) {
+            return Some(Integer::from_bigint(a));
+        }
+        if self.legendre(x)? != 1 {
+            return None;
+        }
+
+        // p - 1 = q * 2^s with q odd, and z a non-square.
+        let n_minus_1: BigInt = n - BigInt::one();
+        let s = n_minus_1.trailing_zeros()?;
+        let q = &n_minus_1 >> s;
+        let mut z = BigInt::from(2);
+        while self.legendre(&Integer::from_bigint(z.clone())) != Some(-1) {
+            z += 1;
+        }
+
+        // invariants: r^2 = a t, and t has order 2^i for some i < m.
+        let mut m = s;
+        let mut c = z.modpow(&q, n);
+        let mut t = a.modpow(&q, n);
+        let mut r = a.modpow(&((&q + BigInt::one()) >> 1), n);
+        while !t.is_one() {
+            let mut i: u64 = 0;
+            let mut t_power = t.clone();
+            while !t_power.is_one() {
+                t_power = &t_power * &t_power % n;
+                i += 1;
+            }
+
+            let b = c.modpow(&(BigInt::one() << (m - i - 1)), n);
+            m = i;
+            c = &b * &b % n;
+            t = &t * &c % n;
+            r = &r * &b % n;
+        }
+
+        let other: BigInt = n - &r;
+        Some(Integer::from_bigint(r.min(other)))
+    }
+
+    // the smallest x >= 0 with g^x = h, by Pohlig–Hellman: with n the order of g, x is found
+    // mod each prime power q^e dividing n, one base q digit at a time, and glued by crt.
+    // Ok(None) if g or h is divisible by p, or h is not a power of g.
+    //
+    // Err(q) if q, the largest prime factor of n, exceeds MAX_DLOG_FACTOR, or if the
+    // factorization of n fails on q, which then exceeds it as well.
+    pub fn dlog(&self, g: &Integer, h: &Integer) -> Result<Option<Integer>, Integer> {
+        let Some(n) = self.order(g)? else {
+            return Ok(None);
+        };
+        let Some(factorization) = n.factor()? else {
+            return Ok(None);
+        };
+        if let Some((q, _)) = factorization.factors.last()
+            && *q > Integer::from_bigint(BigInt::from(MAX_DLOG_FACTOR)) {
+            return Err(q.clone());
+        }
+        if self.modulo(h).is_zero() {
+            return Ok(None);
+        }
+
+        Ok(self.pohlig_hellman(g, h, &n, factorization.factors))
+    }
+
+    fn pohlig_hellman(&self, g: &Integer, h: &Integer, n: &Integer, factors: Vec<(Integer, usize)>) -> Option<Integer> {
+        let mut pairs: Vec<Duo<Integer>> = Vec::new();
+        for (q, e) in factors {
+            let q_e = Integer::from_bigint(num_traits::pow(q.number.clone(), e));
+            let cofactor = Integer::divmod(n, &q_e).first;
+            let g_i = self.pow(g, &cofactor)?;
+            let h_i = self.pow(h, &cofactor)?;
+
+            // gamma has order q; each digit d_k is a log to the base gamma.
+            let top = Integer::from_bigint(num_traits::pow(q.number.clone(), e - 1));
+            let gamma = self.pow(&g_i, &top)?;
+            let mut x_i = Integer::zero();
+            let mut q_k = Integer::one();
+            let mut k: usize = 0;
+            while k < e {
+                let shifted = self.multiply(&self.pow(&g_i, &x_i.neg())?, &h_i);
+                let exponent = Integer::divmod(&top, &q_k).first;
+                let d = self.bsgs(&gamma, &self.pow(&shifted, &exponent)?, &q)?;
+
+                x_i = Integer::add(&x_i, &Integer::multiply(&d, &q_k));
+                q_k = Integer::multiply(&q_k, &q);
+                k += 1;
+            }
+
+            pairs.push(Duo { first: x_i, second: q_e });
+        }
+
+        let x = Integer::crt(&pairs).ok()?.first;
+        // h outside the subgroup generated by g still yields digits; they just do not fit.
+        if self.pow(g, &x)? == self.modulo(h) {
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    // baby-step giant-step: the x in [0, n) with a^x = b, where a has order n.
+    // With m = ceil(sqrt(n)), x = i m + j for a^j in a table of the m baby steps.
+    // n is at most MAX_DLOG_FACTOR, see dlog.
+    fn bsgs(&self, a: &Integer, b: &Integer, n: &Integer) -> Option<Integer> {
+        let mut m = n.number.sqrt();
+        if &m * &m < n.number {
+            m += 1;
+        }
+
+        let mut table: HashMap<BigInt, BigInt> = HashMap::new();
+        let mut power = BigInt::one();
+        let mut j = BigInt::zero();
+        while j < m {
+            table.entry(power.clone()).or_insert(j.clone());
+            power = power * &a.number % &self.p.number;
+            j += 1;
+        }
+
+        let giant = self.pow(a, &Integer::from_bigint(-&m))?;
+        let mut gamma = self.modulo(b);
+        let mut i = BigInt::zero();
+        while i < m {
+            if let Some(j) = table.get(&gamma.number) {
+                return Some(Integer::from_bigint(&i * &m + j));
+            }
+            gamma = self.multiply(&gamma, &giant);
+            i += 1;
+        }
+
+        None
+    }
+
+    fn is_generator(&self, x: &Integer, factors: &[Integer]) -> bool {
+        if self.modulo(x).is_zero() {
+            return false;
+        }
+
+        let order = Integer::subtract(&self.p, &Integer::one());
+        factors.iter().all(|q| {
+            let e = Integer::divmod(&order, q).first;
+            !self.pow(x, &e).is_some_and(|y| y.is_one())
+        })
+    }
+}
+
 // Prime acts on ZPol
 impl Prime {
     pub fn modpol(&self, f: &ZPol) -> ZPol {
@@ -125,44 +483,66 @@ impl Prime {
     }
 }
 
-// HARD part. impl for bezout and divmod.
+// HARD part. impl for divmod; bezout and the rest come from EuclideanDomain.
 impl Prime {
+    // for the algorithms below, whose divisors are nonzero by construction. Division by zero
+    // leaves f reduced as the remainder; input from the user goes through try_divmodpol.
     pub fn divmodpol(&self, f: &ZPol, g:&ZPol) -> Duo<ZPol> {
+        self.try_divmodpol(f, g).unwrap_or_else(|| Duo { first: ZPol::zero(), second: self.modpol(f) })
+    }
+
+    // None if g = 0 mod p.
+    pub fn try_divmodpol(&self, f: &ZPol, g:&ZPol) -> Option<Duo<ZPol>> {
         let zero = ZPol::zero();
 
         if self.eqpol(g, &zero) {
-            panic!("cannot divide by zero")
+            return None;
         }
         
-        let mut dividend = self.modpol(f);
-        let mut dividend_order = dividend.norm();
+        let dividend = self.modpol(f);
+        let dividend_order = dividend.norm();
         let divisor = self.modpol(g);
         
         let divisor_order = divisor.norm();
-        let divisor_coefficient = divisor.vector[divisor_order];
+        let divisor_coefficient = divisor.vector[divisor_order].clone();
 
         if dividend_order < divisor_order || dividend == zero {
-            return Duo::<ZPol>{
+            return Some(Duo::<ZPol>{
                 first: ZPol::zero(),
                 second: dividend,
-            }
+            })
         }
 
-        let mut final_quotient = 
-            ZPol::monomial(&Z::zero(), dividend_order - divisor_order);
-        
-        while dividend_order >= divisor_order && dividend != zero {
-            let temp_coeff = self.divide(&dividend.principal_coefficient(), &divisor_coefficient);
-            let temp_q = ZPol::monomial(&temp_coeff, dividend_order - divisor_order);
-            dividend = self.subpol(&dividend, &self.mulpol(&temp_q, &divisor));
-            dividend_order = dividend.norm();
-            final_quotient = self.addpol(&final_quotient, &temp_q);
+        // the principal coefficient of the divisor is inverted once; each step then
+        // subtracts c * x^shift * divisor from the dividend in place, killing its top term.
+        // (building c * x^shift as a ZPol and multiplying it out is far too slow for GF(p^n).)
+        // Only the nonzero terms of the divisor are visited, as the moduli of GF(p^n) are sparse.
+        let divisor_inv = self.try_inv(&divisor_coefficient)?;
+        let terms: Vec<usize> = (0..=divisor_order).filter(|&j| !divisor.vector[j].is_zero()).collect();
+        let mut quotient: Vec<Z> = vec![Z::zero(); dividend_order - divisor_order + 1];
+        let mut remainder: Vec<Z> = dividend.vector;
+
+        let mut k = dividend_order;
+        while k >= divisor_order {
+            let c = self.multiply(&remainder[k], &divisor_inv);
+            if !c.is_zero() {
+                let shift = k - divisor_order;
+                for &j in &terms {
+                    let term = self.multiply(&c, &divisor.vector[j]);
+                    remainder[shift + j] = self.subtract(&remainder[shift + j], &term);
+                }
+                quotient[shift] = c;
+            }
+            if k == 0 {
+                break;
+            }
+            k -= 1;
         }
 
-        Duo::<ZPol>{
-            first: final_quotient,
-            second: dividend,
-        }
+        Some(Duo::<ZPol>{
+            first: ZPol { vector: quotient }.reduced(),
+            second: ZPol { vector: remainder }.reduced(),
+        })
     }
 
     pub fn regular(&self, f: &ZPol) -> Trio<ZPol> {
@@ -178,7 +558,10 @@ impl Prime {
 
         let pr_coeff = red.principal_coefficient();
 
-        let monicify_coeff = self.inv(&pr_coeff);
+        // a nonzero reduced polynomial has a principal coefficient prime to p.
+        let Some(monicify_coeff) = self.try_inv(&pr_coeff) else {
+            return Trio { first: ZPol::zero(), second: ZPol::zero(), third: ZPol::zero() };
+        };
         let sign_inv = ZPol::as_polynomial(&monicify_coeff);
 
         let sign = ZPol::as_polynomial(&pr_coeff);
@@ -190,120 +573,301 @@ impl Prime {
             third: sign_inv
         }
     }
+}
 
-    pub fn recover(&self, input: &Trio<ZPol>) -> Duo<ZPol>{
-        let m = &input.first;
-        let n = &input.second;
-        let q = &input.third;
+// F(p)[x] as a EuclideanDomain, on polynomials reduced mod p like those PolMod gives.
+// The regular element is the monic one.
+impl EuclideanDomain for Prime {
+    type Elem = ZPol;
 
-        let new_m = self.modpol(n);
-        let new_n = self.subpol(m, &self.mulpol(n, q));
+    fn zero(&self) -> ZPol {
+        ZPol::zero()
+    }
 
-        Duo::<ZPol>{
-            first: new_m,
-            second: new_n,
-        }
+    fn one(&self) -> ZPol {
+        ZPol::one()
     }
 
-    pub fn bezoutpol(&self, input: &Duo<ZPol>) -> Trio<ZPol>{
-        let mut f = self.modpol(&input.first);
-        let mut g = self.modpol(&input.second);
+    fn add(&self, f: &ZPol, g: &ZPol) -> ZPol {
+        self.addpol(f, g)
+    }
 
-        let zero = ZPol::zero();
-        let one = ZPol::one();
-
-        // edge case 1
-        if f == zero && g == zero {
-            return Trio::<ZPol>{
-                first: zero.clone(),
-                second: zero.clone(),
-                third: zero.clone(),
+    fn subtract(&self, f: &ZPol, g: &ZPol) -> ZPol {
+        self.subpol(f, g)
+    }
+
+    fn multiply(&self, f: &ZPol, g: &ZPol) -> ZPol {
+        self.mulpol(f, g)
+    }
+
+    fn neg(&self, f: &ZPol) -> ZPol {
+        self.negpol(f)
+    }
+
+    fn try_divmod(&self, f: &ZPol, g: &ZPol) -> Option<Duo<ZPol>> {
+        self.try_divmodpol(f, g)
+    }
+
+    fn divmod(&self, f: &ZPol, g: &ZPol) -> Duo<ZPol> {
+        self.divmodpol(f, g)
+    }
+
+    fn regular(&self, f: &ZPol) -> Trio<ZPol> {
+        Prime::regular(self, f)
+    }
+}
+
+// Prime acts on ZPol: powers and irreducibility. Groundwork for GF(p^n).
+impl Prime {
+    // f^e mod m by square-and-multiply. m must be nonzero and e non-negative.
+    pub fn powmodpol(&self, f: &ZPol, e: &Integer, m: &ZPol) -> ZPol {
+        let base = self.divmodpol(f, m).second;
+        let mut res = self.divmodpol(&ZPol::one(), m).second;
+
+        let mut i = e.number.bits();
+        while i > 0 {
+            i -= 1;
+            res = self.divmodpol(&self.mulpol(&res, &res), m).second;
+            if e.number.bit(i) {
+                res = self.divmodpol(&self.mulpol(&res, &base), m).second;
             }
         }
 
-        // edge case 2
-        if f != zero && g == zero {
-            let reg = self.regular(&f);
+        res
+    }
 
-            return Trio::<ZPol> {
-                first: reg.third,
-                second: zero.clone(),
-                third: reg.second,
-            }
+    // g^p mod m. Over F(p), g(x)^p = g(x^p), as the coefficients are their own p-th powers;
+    // spreading them out is cheaper than squaring as long as p is at most the degree of m.
+    fn frobeniuspol(&self, g: &ZPol, m: &ZPol) -> ZPol {
+        let p = self.p.norm();
+        if p > m.norm() {
+            return self.powmodpol(g, &self.p, m);
         }
 
-        // edge case 3
-        if f == zero && g != zero {
-            let reg = self.regular(&g);
+        let red = self.modpol(g);
+        let ord = red.norm();
+        let mut target: Vec<Z> = vec![Z::zero(); ord * p + 1];
+        let mut i: usize = 0;
+        while i <= ord {
+            target[i * p] = red.vector[i].clone();
+            i += 1;
+        }
 
-            return Trio::<ZPol> {
-                first: zero.clone(),
-                second: reg.third,
-                third: reg.second,
+        self.divmodpol(&ZPol { vector: target }, m).second
+    }
+
+    // Ben-Or's test. f of degree n > 0 is irreducible iff gcd(x^(p^k) - x, f) = 1
+    // for k = 1, ..., n/2, as x^(p^k) - x is the product of all monic irreducibles of
+    // degree dividing k. Most polynomials have a small factor and are rejected early.
+    // Constants (including zero) are not irreducible.
+    pub fn is_irreducible(&self, f: &ZPol) -> bool {
+        let f = self.modpol(f);
+        let n = f.norm();
+        if n == 0 {
+            return false;
+        }
+
+        let x = ZPol::monomial(&Z::one(), 1);
+
+        // frob = x^(p^k) mod f, each obtained as the p-th power of the previous one.
+        let mut frob = self.divmodpol(&x, &f).second;
+        let mut k: usize = 1;
+        while k <= n / 2 {
+            frob = self.frobeniuspol(&frob, &f);
+            let h = self.subpol(&frob, &x);
+            if self.gcdpol(&f, &h) != ZPol::one() {
+                return false;
             }
+            k += 1;
         }
 
-        let max_step: usize = std::cmp::max(f.norm(), g.norm()) + 1;
-        let mut vec_q: Vec<ZPol> = vec![zero.clone(); max_step+1];
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let mut mod_temp = self.divmodpol(&f, &g);
-        let mut q = mod_temp.first; let mut r = mod_temp.second;
+    fn big(input: &str) -> BigInt {
+        input.parse().unwrap()
+    }
 
-        // edge case
-        if r == zero {
-            return Trio::<ZPol> {
-                first: zero.clone(),
-                second: one.clone(),
-                third: g.clone(),
-            }
+    #[test]
+    fn is_prime_small_and_large() {
+        let primes = [2, 97, 101, 1000000007, 2147483647];
+        let composites = [0, 1, 91, 561, 2047, 3215031751, 1000000007 * 998244353];
+        assert!(primes.iter().all(|&n| is_prime(&Integer::new(n))));
+        assert!(!composites.iter().any(|&n| is_prime(&Integer::new(n))));
+
+        // 2^127 - 1 and 2^521 - 1 are Mersenne primes; 2^128 + 1 and 2^523 - 1 are not.
+        let one = BigInt::one();
+        assert!(is_prime(&Integer::from_bigint((&one << 127u32) - &one)));
+        assert!(is_prime(&Integer::from_bigint((&one << 521u32) - &one)));
+        assert!(!is_prime(&Integer::from_bigint((&one << 128u32) + &one)));
+        assert!(!is_prime(&Integer::from_bigint((&one << 523u32) - &one)));
+    }
+
+    #[test]
+    fn strong_lucas_pseudoprimes() {
+        // the first strong Lucas pseudoprimes for Selfridge's parameters. Miller–Rabin
+        // to the base 2 catches them, as BPSW expects.
+        for n in [5459, 5777, 10877, 16109, 18971] {
+            let n = BigInt::from(n);
+            assert!(is_strong_lucas_probable_prime(&n));
+            assert!(!is_strong_probable_prime(&n, 2));
         }
 
-        let mut done: bool = { self.divmodpol(&g, &r).second == zero };
+        // and the other way around: strong pseudoprimes to the base 2.
+        for n in [2047i64, 3215031751, 3825123056546413051] {
+            let n = BigInt::from(n);
+            assert!(is_strong_probable_prime(&n, 2));
+            assert!(!is_strong_lucas_probable_prime(&n));
+        }
 
-        let mut i: usize = 1;
-        vec_q[i] = q;
+        assert!(is_strong_lucas_probable_prime(&big("618970019642690137449562111")));
+        assert!(!is_strong_lucas_probable_prime(&big("1427247692705959880439315947500961989719490561")));
+    }
 
-        while !done {
-            f = g.clone();
-            g = r.clone();
-        
-            mod_temp = self.divmodpol(&f, &g);
+    fn zpol(coeffs: &[int]) -> ZPol {
+        ZPol { vector: coeffs.iter().map(|&c| Integer::new(c)).collect() }
+    }
 
-            q = mod_temp.first;
-            r = mod_temp.second;
+    #[test]
+    fn crt_over_f5() {
+        // X = 1 mod x and X = x mod x^2 + 1 over F(5): X = x^2 + x + 1 mod x^3 + x.
+        let p = Prime::try_new(5).unwrap();
+        let res = p.crt(&[
+            Duo { first: zpol(&[1]), second: zpol(&[0, 1]) },
+            Duo { first: zpol(&[0, 1]), second: zpol(&[1, 0, 1]) },
+        ]).ok().unwrap();
+
+        assert_eq!(res.first, zpol(&[1, 1, 1]));
+        assert_eq!(res.second, zpol(&[0, 1, 0, 1]));
+
+        // X = 0 mod x and X = 1 mod 2x: inconsistent.
+        assert!(p.crt(&[
+            Duo { first: zpol(&[0]), second: zpol(&[0, 1]) },
+            Duo { first: zpol(&[1]), second: zpol(&[0, 2]) },
+        ]).is_err());
+    }
 
-            i += 1;
-            vec_q[i] = q;
+    #[test]
+    fn sqrt_of_every_residue() {
+        // p = 3 mod 4, and p = 1 mod 8 with 2^3 up to 2^6 dividing p - 1.
+        for p in [2, 3, 7, 11, 17, 41, 97, 193] {
+            let prime = Prime::try_new(p).unwrap();
+            let mut squares = 0;
+            for a in 0..p {
+                let a = Integer::new(a);
+                match prime.sqrt(&a) {
+                    Some(r) => {
+                        assert_eq!(prime.multiply(&r, &r), a);
+                        assert!(r <= prime.neg(&r));
+                        squares += 1;
+                    }
+                    None => assert_eq!(prime.legendre(&a), Some(-1)),
+                }
+            }
+            // 0 and the (p - 1) / 2 nonzero squares.
+            assert_eq!(squares, if p == 2 { 2 } else { (p + 1) / 2 });
+        }
+    }
 
-            done = self.divmodpol(&g, &r).second == zero;  
+    #[test]
+    fn sqrt_with_large_power_of_two() {
+        // 998244353 = 119 * 2^23 + 1, where Tonelli–Shanks takes the most steps.
+        let p = Prime::try_new(998244353).unwrap();
+        for a in [2, 5, 123456789, -1] {
+            let a = Integer::new(a);
+            let square = p.multiply(&a, &a);
+            let r = p.sqrt(&square).unwrap();
+            assert!(r == p.modulo(&a) || r == p.neg(&a));
         }
+        assert_eq!(p.legendre(&Integer::new(3)), Some(-1));
+        assert!(p.sqrt(&Integer::new(3)).is_none());
+    }
 
-        let mut duo = Duo::<ZPol>{first: one.clone(), second: self.negpol(&vec_q[i].clone())};
-    
-        while i > 1 {
-            let trio = Trio::<ZPol>{
-                first: duo.first,
-                second: duo.second, 
-                third: vec_q[i-1].clone(),
-            };
-        
-            duo = self.recover(&trio);
-            i -= 1;
+    #[test]
+    fn legendre_symbol() {
+        let p = Prime::try_new(7).unwrap();
+        let symbols: Vec<Option<int>> = (0..7).map(|a| p.legendre(&Integer::new(a))).collect();
+        assert_eq!(symbols, vec![Some(0), Some(1), Some(1), Some(-1), Some(1), Some(-1), Some(-1)]);
+        assert_eq!(Prime::try_new(2).unwrap().legendre(&Integer::one()), None);
+    }
+
+    #[test]
+    fn dlog_of_every_unit() {
+        // p - 1 = 2^8 takes one digit at a time; 101 - 1 = 2^2 * 5^2 needs crt too.
+        for (p, g) in [(257, 3), (101, 2)] {
+            let prime = Prime::try_new(p).unwrap();
+            let g = Integer::new(g);
+            assert_eq!(prime.is_primroot(&g), Ok(true));
+            for h in 1..p {
+                let h = Integer::new(h);
+                let x = prime.dlog(&g, &h).unwrap().unwrap();
+                assert!(x < Integer::new(p - 1));
+                assert_eq!(prime.pow(&g, &x), Some(h));
+            }
         }
+    }
+
+    #[test]
+    fn dlog_in_a_subgroup() {
+        // 3 has order 3 mod 13: its powers are 1, 3 and 9.
+        let p = Prime::try_new(13).unwrap();
+        let g = Integer::new(3);
+        assert_eq!(p.dlog(&g, &Integer::new(9)), Ok(Some(Integer::new(2))));
+        assert_eq!(p.dlog(&g, &Integer::new(14)), Ok(Some(Integer::zero())));
+        assert_eq!(p.dlog(&g, &Integer::new(2)), Ok(None));
+        assert_eq!(p.dlog(&g, &Integer::zero()), Ok(None));
+        assert_eq!(p.dlog(&Integer::zero(), &g), Ok(None));
+    }
+
+    #[test]
+    fn dlog_large_prime() {
+        let p = Prime::try_new(1000000007).unwrap();
+        let (g, h) = (Integer::new(5), Integer::new(123456789));
+        let x = p.dlog(&g, &h).unwrap().unwrap();
+
+        assert_eq!(x, Integer::new(981640996));
+        assert_eq!(p.pow(&g, &x), Some(h));
+    }
+
+    #[test]
+    fn dlog_refuses_large_factor() {
+        // p - 1 = 2 * 100000000000000181, beyond MAX_DLOG_FACTOR.
+        let p = Prime::try_new(200000000000000363).unwrap();
+        let q = Integer::new(100000000000000181);
 
-        let reg_r = self.regular(&r);
-        let monicifier = reg_r.third;
+        assert_eq!(p.dlog(&Integer::new(2), &Integer::new(3)), Err(q));
+    }
 
-        Trio::<ZPol>{
-            first: self.mulpol(&duo.first, &monicifier),
-            second: self.mulpol(&duo.second, &monicifier),
-            third: reg_r.second,
+    #[test]
+    fn bsgs_within_order() {
+        // 2 has order 11 mod 23.
+        let p = Prime::try_new(23).unwrap();
+        let a = Integer::new(2);
+        let n = Integer::new(11);
+        for x in 0..11 {
+            let b = p.pow(&a, &Integer::new(x)).unwrap();
+            assert_eq!(p.bsgs(&a, &b, &n), Some(Integer::new(x)));
         }
+        assert_eq!(p.bsgs(&a, &Integer::new(5), &n), None);
     }
 
-    pub fn unwrapped_bezoutpol (&self, f: &ZPol, g: &ZPol) -> (ZPol, ZPol, ZPol){
-        let duo = Duo::<ZPol> {first: f.clone(), second: g.clone()};
-        let trio = self.bezoutpol(&duo);
-        (trio.first, trio.second, trio.third)
+    #[test]
+    fn solve_linear_over_f5() {
+        let p = Prime::try_new(5).unwrap();
+        let (a, b, c) = (zpol(&[1, 0, 1]), zpol(&[3, 2]), zpol(&[1, 1]));
+        let sol = p.solve_linear(&a, &b, &c).ok().unwrap();
+
+        assert_eq!(sol.x, zpol(&[1]));
+        assert_eq!(sol.step_x, zpol(&[4, 1]));
+        for t in [zpol(&[0]), zpol(&[1]), zpol(&[2, 3])] {
+            let x = p.addpol(&sol.x, &p.mulpol(&sol.step_x, &t));
+            let y = p.subpol(&sol.y, &p.mulpol(&sol.step_y, &t));
+            assert_eq!(p.addpol(&p.mulpol(&a, &x), &p.mulpol(&b, &y)), c);
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,123 +1,113 @@
 use super::*;
+use num_integer::Integer as _;
+use num_traits::{Signed, One, Zero};
 
 pub struct NumPair {
-    pub first: int,
-    pub second: int,
+    pub first: BigInt,
+    pub second: BigInt,
 }
 
 impl NumPair {
-    pub fn gcd (&self) -> int {
-        if self.first ==0 || self.second ==0 {
-            return 0;
+    pub fn gcd (&self) -> BigInt {
+        if self.first.is_zero() || self.second.is_zero() {
+            return BigInt::zero();
         }
 
-        let mut r: int = 1;
-        let mut a: int = self.first.abs();
-        let mut b: int = self.second.abs();
-
-        if a < b {
-            let temp = a;
-            a = b;
-            b = temp;
-        }
-
-        while r != 0 {
-            r = a % b;
-
-         
-            a = b; b = r;
-        }
-
-        a
-        
+        self.first.gcd(&self.second)
     }
 
     pub fn reduced_pair (&self) -> Self{
-        if self.first == 0 && self.second == 0 {
-            return Self::new(0,0);
+        if self.first.is_zero() && self.second.is_zero() {
+            return Self::new(BigInt::zero(), BigInt::zero());
         }
-        if self.second == 0 {
-            return Self::new(1,0);
+        if self.second.is_zero() {
+            return Self::new(BigInt::one(), BigInt::zero());
         }
-        if self.first == 0 {
-            return Self::new(0,1);
+        if self.first.is_zero() {
+            return Self::new(BigInt::zero(), BigInt::one());
         }
-        let mut x = self.first;
-        let mut y = self.second;
+        let mut x = self.first.clone();
+        let mut y = self.second.clone();
 
-        if y < 0 {
+        if y.is_negative() {
             x = -x; y = -y;
         }
 
         let a = self.gcd();
 
         Self {
-            first: x / a,
-            second: y / a,
+            first: x / &a,
+            second: y / &a,
         }
     }
 }
 
 impl NumPair {
-    pub fn new(x: int, y: int) -> Self {
-        Self { first: (x), second: (y) }
+    pub fn new(x: BigInt, y: BigInt) -> Self {
+        Self { first: x, second: y }
     }
 
     pub fn display(&self){
         println!("({}, {})",  self.first, self.second);
     }
     
-    pub fn to_rational(&self) -> Rational{
-        if self.second ==0 {panic!("Denominator must not be 0.");}
+    // None if the denominator is 0.
+    pub fn to_rational(&self) -> Option<Rational>{
+        if self.second.is_zero() {return None;}
     
         let reduced = self.reduced_pair();
     
-        Rational { numerator: reduced.first, denominator: reduced.second }
+        Some(Rational { numerator: reduced.first, denominator: reduced.second })
     }
 }
 
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct Rational{
-    pub numerator: int,
-    pub denominator: int,
+    pub numerator: BigInt,
+    pub denominator: BigInt,
 }
 
 impl Rational {
     pub fn to_pair (&self) -> NumPair {
-        NumPair::new(self.numerator, self.denominator)
+        NumPair::new(self.numerator.clone(), self.denominator.clone())
     }
     
+    // the denominator of a Rational is never 0, and stays so.
     pub fn reduced(&self) -> Self {
-        self.to_pair().to_rational()
+        let reduced = self.to_pair().reduced_pair();
+
+        Rational { numerator: reduced.first, denominator: reduced.second }
     }
 
-    pub fn new (num: int, denom: int) -> Rational {
+    // None if denom is 0.
+    pub fn new (num: int, denom: int) -> Option<Rational> {
+        NumPair::new(BigInt::from(num), BigInt::from(denom)).to_rational()
+    }
+
+    // None if denom is 0.
+    pub fn from_bigints (num: BigInt, denom: BigInt) -> Option<Rational> {
         NumPair::new(num, denom).to_rational()
     }
 
     pub fn is_integer(&self) -> bool {
-        let reduced = self.reduced();
-        match reduced.denominator {
-            1 => true,
-            _ => false,
-        }
+        self.reduced().denominator.is_one()
     }
 
     pub fn to_integer(&self) -> Option<Integer> {
         let reduced = self.reduced();
-        match reduced.denominator {
-            1 => Some(Integer::new(reduced.numerator)),
-            _ => None,
+        match reduced.denominator.is_one() {
+            true => Some(Integer::from_bigint(reduced.numerator)),
+            false => None,
         }
     }
 
     pub fn is_positive(&self) -> bool {
-        self.reduced().numerator > 0
+        self.reduced().numerator.is_positive()
     }
 
     pub fn is_negative(&self) -> bool {
-        self.reduced().numerator < 0  
+        self.reduced().numerator.is_negative()
     }
 
     pub fn abs(&self) -> Self {
@@ -131,8 +121,8 @@ impl Rational {
     // e.g. 0 -> 0; 1 -> 1; 0.5 -> 0; 1.2 -> 1; -0.2 -> -1; 
     pub fn floor(&self) -> Integer {
         // floor function takes an integer to itself
-        if self.is_integer() {
-            return self.to_integer().unwrap();
+        if let Some(int) = self.to_integer() {
+            return int;
         }
 
         // The following is non-integer case;
@@ -140,11 +130,11 @@ impl Rational {
         let abs = self.abs();
 
         let num = abs.numerator; let den = abs.denominator;
-        let q = num.div_euclid(den);
+        let q = num.div_floor(&den);
         
         match self.is_negative() {
-            true => Integer::new(-(q+1)),
-            false => Integer::new(q)
+            true => Integer::from_bigint(-(q + BigInt::one())),
+            false => Integer::from_bigint(q)
         }
     }
 
@@ -152,48 +142,44 @@ impl Rational {
     // If not, return floor + 1.
     // We always have [n, n+0.5] -> n; (n+0.5, n+1) -> n+1. 
     pub fn nearest_integer(&self) -> Integer {
-        let half = Rational::new(1,2);
+        let half = Rational { numerator: BigInt::one(), denominator: BigInt::from(2) };
         let floor = self.floor();
-        let diff = *self - floor.to_rational();
+        let diff = self.clone() - floor.to_rational();
 
         if diff <= half {
             floor
         } else {
-            Integer::new(floor.number+1)
+            Integer::from_bigint(floor.number + BigInt::one())
         }
     }
 }
 
 impl Field for Rational {
     fn zero() -> Self {
-        Rational{numerator: 0, denominator: 1}
+        Rational{numerator: BigInt::zero(), denominator: BigInt::one()}
     }
     
     fn one() -> Self {
-        Rational{numerator: 1, denominator: 1}
+        Rational{numerator: BigInt::one(), denominator: BigInt::one()}
     }
 
     fn equal(x: &Self, y: &Self) -> bool {
-        x.numerator * y.denominator == x.denominator * y.numerator
+        &x.numerator * &y.denominator == &x.denominator * &y.numerator
     }
 
     fn add(x: &Self, y: &Self ) -> Self{
-        let p = x.numerator * y.denominator + y.numerator * x.denominator;
-        let q = x.denominator * y.denominator;
+        let p = &x.numerator * &y.denominator + &y.numerator * &x.denominator;
+        let q = &x.denominator * &y.denominator;
 
-        NumPair::new(p,q).to_rational()
+        Rational { numerator: p, denominator: q }.reduced()
     }
 
-    fn inv(&self) -> Self {
-        if *self == Self::zero() {
-            panic!("zero is not invertible.");
-        }
-
-        NumPair::new(self.denominator, self.numerator).to_rational()
+    fn inv(&self) -> Option<Self> {
+        NumPair::new(self.denominator.clone(), self.numerator.clone()).to_rational()
     }
 
     fn neg(&self) -> Self {
-        NumPair::new(- self.numerator, self.denominator).to_rational()
+        Rational { numerator: - &self.numerator, denominator: self.denominator.clone() }.reduced()
     }
 
     fn subtract(x: &Self, y: &Self) -> Self{
@@ -201,23 +187,19 @@ impl Field for Rational {
     }
 
     fn multiply(x: &Self, y: &Self) -> Self{
-        let p = x.numerator * y.numerator;
-        let q = x.denominator * y.denominator;
+        let p = &x.numerator * &y.numerator;
+        let q = &x.denominator * &y.denominator;
 
-        NumPair::new(p,q).to_rational()
+        Rational { numerator: p, denominator: q }.reduced()
     }
 
-    fn divide(x: &Self, y: &Self) -> Self {
-        if *y == Self::zero() {
-            panic!("denominator cannot be zero.");
-        }
-
-        Self::multiply(x, &(y.inv()))
+    fn divide(x: &Self, y: &Self) -> Option<Self> {
+        Some(Self::multiply(x, &y.inv()?))
     }
 }
 
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct ComplexRational{
     pub real: Rational,
     pub imag: Rational,
@@ -232,16 +214,15 @@ impl ComplexRational {
     }
 
     pub fn reduced(&self) -> Self {
-        let reduced_real = self.real_to_pair().to_rational();
-        let reduced_imag = self.imag_to_pair().to_rational();
-        ComplexRational { real: reduced_real, imag: reduced_imag }
+        ComplexRational { real: self.real.reduced(), imag: self.imag.reduced() }
     }
 
-    pub fn new (real_num: int, real_denom: int, imag_num: int, imag_denom: int) -> Self {
-        ComplexRational {
-            real: NumPair::new(real_num, real_denom).to_rational(),
-            imag: NumPair::new(imag_num, imag_denom).to_rational(),
-        }
+    // None if a denominator is 0.
+    pub fn new (real_num: int, real_denom: int, imag_num: int, imag_denom: int) -> Option<Self> {
+        Some(ComplexRational {
+            real: Rational::new(real_num, real_denom)?,
+            imag: Rational::new(imag_num, imag_denom)?,
+        })
     }
 
     pub fn is_gauss_integer(&self) -> bool {
@@ -249,13 +230,9 @@ impl ComplexRational {
     }
 
     pub fn to_gauss_integer(&self) -> Option<GaussInteger> {
-        if !self.is_gauss_integer() {
-            return None;
-        }
-
         let res = GaussInteger {
-            real: self.real.to_integer().unwrap(),
-            imag: self.imag.to_integer().unwrap(),
+            real: self.real.to_integer()?,
+            imag: self.imag.to_integer()?,
         };
 
         Some(res)
@@ -270,7 +247,7 @@ impl ComplexRational {
             return None;
         }
 
-        Some(self.real)
+        Some(self.real.clone())
     }
 
     pub fn is_pure_imag(&self) -> bool {
@@ -289,42 +266,45 @@ impl ComplexRational {
 impl Field for ComplexRational {
     fn zero() -> Self {
         ComplexRational {
-            real: Rational {numerator: 0, denominator: 1},
-            imag: Rational {numerator: 0, denominator: 1}
+            real: Rational::zero(),
+            imag: Rational::zero()
         }
     }
 
     fn one() -> Self {
         ComplexRational {
-            real: Rational {numerator: 1, denominator: 1},
-            imag: Rational {numerator: 0, denominator: 1}
+            real: Rational::one(),
+            imag: Rational::zero()
         }
     }
 
     fn add (x: &Self, y:& Self) -> Self {
-        let a = x.real; let b = x.imag;
-        let c = y.real; let d = y.imag;
+        let a = &x.real; let b = &x.imag;
+        let c = &y.real; let d = &y.imag;
         ComplexRational {
-            real: Rational::add(&a,&c),
-            imag: Rational::add(&b,&d)
+            real: Rational::add(a,c),
+            imag: Rational::add(b,d)
         }
     }
 
     fn subtract (x: &Self, y:& Self) -> Self {
-        let a = x.real; let b = x.imag;
-        let c = y.real; let d = y.imag;
+        let a = &x.real; let b = &x.imag;
+        let c = &y.real; let d = &y.imag;
         ComplexRational {
-            real: Rational::subtract(&a,&c),
-            imag: Rational::subtract(&b,&d)
+            real: Rational::subtract(a,c),
+            imag: Rational::subtract(b,d)
         }
     }
 
     // (a+bi)*(c+di) = (ac-bd) + (bc+ad) i
     fn multiply (x: &Self, y:& Self) -> Self {
-        let a = x.real; let b = x.imag;
-        let c = y.real; let d = y.imag;
+        let a = &x.real; let b = &x.imag;
+        let c = &y.real; let d = &y.imag;
 
-        ComplexRational { real: a*c-b*d, imag:  b*c+a*d}
+        ComplexRational {
+            real: Rational::subtract(&Rational::multiply(a,c), &Rational::multiply(b,d)),
+            imag: Rational::add(&Rational::multiply(b,c), &Rational::multiply(a,d))
+        }
     }
 
     fn equal(x: &Self, y: &Self) -> bool {
@@ -332,25 +312,19 @@ impl Field for ComplexRational {
     }
 
     fn neg(&self) -> Self {
-        ComplexRational { real: -self.real, imag: -self.imag}
+        ComplexRational { real: self.real.neg(), imag: self.imag.neg()}
     }
 
-    // 1/(a+bi) = (a-bi)/(a^2+b^2)
-    fn inv(&self) -> Self {
-        if self.is_zero() {
-            panic!("zero is not invertible.")
-        }
-        let a = self.real; let b = self.imag;
-        let n = a*a+b*b;
+    // 1/(a+bi) = (a-bi)/(a^2+b^2), where a^2+b^2 is 0 only for zero.
+    fn inv(&self) -> Option<Self> {
+        let a = &self.real; let b = &self.imag;
+        let n = Rational::add(&Rational::multiply(a,a), &Rational::multiply(b,b));
 
-        ComplexRational { real: a/n, imag: (-b)/n }
+        Some(ComplexRational { real: Rational::divide(a,&n)?, imag: Rational::divide(&b.neg(),&n)? })
     }
 
-    fn divide (x: &Self, y: &Self) -> Self {
-        if y.is_zero() {
-            panic!("zero cannot be a denominator.")
-        }
-        Self::multiply(x, &y.inv())
+    fn divide (x: &Self, y: &Self) -> Option<Self> {
+        Some(Self::multiply(x, &y.inv()?))
     }
 }
 
@@ -358,7 +332,8 @@ impl Field for ComplexRational {
 mod chore {
     use super::{ComplexRational, Rational, Field};
 
-    // Chore: overloading arithmetic operators (+ - * / = < >) for Rational 
+    // Chore: overloading arithmetic operators (+ - * = < >) for Rational. Division may fail,
+    // so it has no operator; see Field::divide. 
     impl std::cmp::PartialEq for Rational {
         fn eq(&self, other: &Self) -> bool {
             Rational::equal(self, other)
@@ -369,16 +344,8 @@ mod chore {
 
     impl std::cmp::Ord for Rational {
         fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-            use std::cmp::Ordering;
-
             let res = Rational::subtract(self, other);
-            if res.numerator < 0 {
-                return Ordering::Less;
-            } else if res.numerator > 0 {
-                return Ordering::Greater;
-            } else {
-                return Ordering::Equal;
-            }
+            res.numerator.sign().cmp(&num_bigint::Sign::NoSign)
         }
     }
 
@@ -420,18 +387,10 @@ mod chore {
         }
     }
 
-    impl std::ops::Div for Rational {
-        type Output = Self;
-
-        fn div(self, rhs: Self) -> Self {
-            <Self as Field>::divide(&self, &rhs)
-        }
-    }
-
-    // Chore: overloading arithmetic operators (+ - * / =) for ComplexRational
+    // Chore: overloading arithmetic operators (+ - * =) for ComplexRational
     impl std::cmp::PartialEq for ComplexRational {
         fn eq(&self, other: &Self) -> bool {
-            <ComplexRational as Field>::equal(&self, other)
+            <ComplexRational as Field>::equal(self, other)
         }
     }
 
@@ -468,13 +427,37 @@ mod chore {
             <Self as Field>::neg(&self)
         }
     }
+}
 
-    impl std::ops::Div for ComplexRational {
-        type Output = Self;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        fn div(self, rhs: Self) -> Self {
-            <Self as Field>::divide(&self, &rhs)
-        }
+    #[test]
+    fn division_by_zero_is_none() {
+        assert_eq!(Rational::new(1, 0), None);
+        assert_eq!(Rational::zero().inv(), None);
+        assert_eq!(Rational::divide(&Rational::one(), &Rational::zero()), None);
+        assert_eq!(ComplexRational::zero().inv(), None);
+
+        let half = Rational::new(2, 4).unwrap();
+        assert_eq!(Rational::divide(&Rational::one(), &half), Rational::new(2, 1));
+
+        // (1 + i)^-1 = (1 - i)/2.
+        let res = ComplexRational::new(1, 1, 1, 1).unwrap().inv();
+        assert_eq!(res, ComplexRational::new(1, 2, -1, 2));
     }
-}
 
+    #[test]
+    fn fractions_are_reduced() {
+        let x = Rational::from_bigints(BigInt::from(-6), BigInt::from(-4)).unwrap();
+        assert_eq!(x.numerator, BigInt::from(3));
+        assert_eq!(x.denominator, BigInt::from(2));
+
+        // 10^20/3 + 2 * 10^20/3 = 10^20, far beyond i64.
+        let big = BigInt::from(10u64).pow(20u32);
+        let third = Rational::from_bigints(big.clone(), BigInt::from(3)).unwrap();
+        let sum = Rational::add(&third, &Rational::multiply(&third, &Rational::new(2, 1).unwrap()));
+        assert_eq!(sum.to_integer(), Some(Integer::from_bigint(big)));
+    }
+}
@@ -1,22 +1,25 @@
 use crate::{Duo, Trio};
+use std::marker::PhantomData;
 
-pub trait Field: Copy + Clone + std::fmt::Display + PartialEq {
+pub trait Field: Clone + std::fmt::Display + PartialEq {
     fn zero() -> Self;
     fn one() -> Self;
     fn add (x: &Self, y:& Self) -> Self;
     fn subtract (x: &Self, y:& Self) -> Self;
     fn multiply (x: &Self, y:& Self) -> Self;
-    fn divide (x: &Self, y:& Self) -> Self;
-    fn inv(&self) -> Self;
+    // None if y is zero.
+    fn divide (x: &Self, y:& Self) -> Option<Self>;
+    // None if self is zero.
+    fn inv(&self) -> Option<Self>;
     fn neg(&self) -> Self;
     fn equal(x: &Self, y: &Self) -> bool;
 
     // derived methods. no need to implement manually.
     fn is_zero(&self) -> bool {
-        Self::equal(&self, &Self::zero())
+        Self::equal(self, &Self::zero())
     }
     fn is_one(&self) -> bool {
-        Self::equal(&self, &Self::one())
+        Self::equal(self, &Self::one())
     }
 }
 
@@ -33,8 +36,15 @@ pub trait EuclideanRing: Clone + std::fmt::Display + PartialEq {
     // gives the result of euclidean division 
     // return value: (quotient, remainder)
     //
-    // it panics if y = 0.
-    fn divmod(x: &Self, y: &Self) -> Duo<Self>;
+    // None if y = 0.
+    fn try_divmod(x: &Self, y: &Self) -> Option<Duo<Self>>;
+
+    // try_divmod for the algorithms below, whose divisors are nonzero by construction.
+    // Division by zero leaves x as the remainder, since x = 0 * 0 + x; input from the user
+    // goes through try_divmod.
+    fn divmod(x: &Self, y: &Self) -> Duo<Self> {
+        Self::try_divmod(x, y).unwrap_or_else(|| Duo { first: Self::zero(), second: x.clone() })
+    }
 
     // gives a canonical element "reg" to represent the ideal generated by self,
     // obtained by dividing a inversible element "sign".
@@ -47,10 +57,9 @@ pub trait EuclideanRing: Clone + std::fmt::Display + PartialEq {
     fn regular(&self) -> Trio<Self>; 
 
     // use divmod to find its inverse.
-    // if the element has no inversion, then None.
-    // panics if self == zero.
+    // if the element has no inversion, zero included, then None.
     fn try_inv(&self) -> Option<Self> {
-        let duo = Self::divmod(&Self::one(), self);
+        let duo = Self::try_divmod(&Self::one(), self)?;
         if duo.second == Self::zero() {
             return Some(duo.first);
         }
@@ -65,44 +74,100 @@ pub trait EuclideanRing: Clone + std::fmt::Display + PartialEq {
         Self::equal(self, &Self::one())
     }
 
+    // the algorithms below are those of EuclideanDomain, run on RingOf<Self>.
     fn gcd(input: &Duo<Self>) -> Self {
+        RingOf::<Self>::default().gcd(input)
+    }
+
+    fn bezout(input: &Duo<Self>) -> Trio<Self> {
+        RingOf::<Self>::default().bezout(input)
+    }
+
+    fn bezout_all(inputs: &[Self]) -> (Vec<Self>, Self) {
+        RingOf::<Self>::default().bezout_all(inputs)
+    }
+
+    fn lcm(x: &Self, y: &Self) -> Self {
+        RingOf::<Self>::default().lcm(x, y)
+    }
+
+    fn solve_linear(a: &Self, b: &Self, c: &Self) -> Result<LinearSolution<Self>, Self> {
+        RingOf::<Self>::default().solve_linear(a, b, c)
+    }
+
+    fn euclid_trace(input: &Duo<Self>) -> Vec<EuclidStep<Self>> {
+        RingOf::<Self>::default().euclid_trace(input)
+    }
+
+    fn unwrapped_bezout(f: &Self, g: &Self) -> (Self, Self, Self) {
+        RingOf::<Self>::default().unwrapped_bezout(f, g)
+    }
+
+    fn crt(pairs: &[Duo<Self>]) -> Result<Duo<Self>, (usize, Self)> {
+        RingOf::<Self>::default().crt(pairs)
+    }
+}
+
+// a Euclidean ring given by a value rather than by a type, like F(p)[x] for a prime p known
+// only at run time. The algorithms on Euclidean rings are written here once: a EuclideanRing
+// runs them through RingOf, and Prime implements this for its polynomials.
+pub trait EuclideanDomain {
+    type Elem: Clone + PartialEq;
+
+    fn zero(&self) -> Self::Elem;
+    fn one(&self) -> Self::Elem;
+    fn add(&self, x: &Self::Elem, y: &Self::Elem) -> Self::Elem;
+    fn subtract(&self, x: &Self::Elem, y: &Self::Elem) -> Self::Elem;
+    fn multiply(&self, x: &Self::Elem, y: &Self::Elem) -> Self::Elem;
+    fn neg(&self, x: &Self::Elem) -> Self::Elem;
+
+    // see EuclideanRing::try_divmod, divmod and regular.
+    fn try_divmod(&self, x: &Self::Elem, y: &Self::Elem) -> Option<Duo<Self::Elem>>;
+
+    fn divmod(&self, x: &Self::Elem, y: &Self::Elem) -> Duo<Self::Elem> {
+        self.try_divmod(x, y).unwrap_or_else(|| Duo { first: self.zero(), second: x.clone() })
+    }
+
+    fn regular(&self, x: &Self::Elem) -> Trio<Self::Elem>;
+
+    fn gcd(&self, input: &Duo<Self::Elem>) -> Self::Elem {
         let mut a = input.first.clone();
         let mut b = input.second.clone();
-        let zero = Self::zero();
-        let mut r = Self::divmod(&mut a, &mut b).second;
+        let zero = self.zero();
+        let mut r = self.divmod(&a, &b).second;
 
-        while !Self::equal(&r, &zero){
+        while r != zero {
             a = b.clone(); b = r.clone();
-            r = Self::divmod(&mut a, &mut b).second;
+            r = self.divmod(&a, &b).second;
         }
 
         b
     }
 
-    fn recover(input: &Trio<Self>) -> Duo<Self> {
+    fn recover(&self, input: &Trio<Self::Elem>) -> Duo<Self::Elem> {
         let m = input.first.clone();
         let n = input.second.clone();
         let q = input.third.clone();
 
         let new_m = n.clone();
-        let new_n = Self::subtract(&m, &Self::multiply(&n, &q));
+        let new_n = self.subtract(&m, &self.multiply(&n, &q));
 
-        Duo::<Self>{
+        Duo {
             first: new_m,
             second: new_n,
         }
     }
 
     // put in (F, G), return (U, V, H) such that FU + FV = H.
-    fn bezout(input: &Duo<Self>) -> Trio<Self>{
+    fn bezout(&self, input: &Duo<Self::Elem>) -> Trio<Self::Elem> {
         let mut a = input.first.clone();
         let mut b = input.second.clone();
-        let zero = Self::zero();
-        let one = Self::one();
+        let zero = self.zero();
+        let one = self.one();
 
         // edge cases
         if a == zero && b == zero {
-            return Trio::<Self>{
+            return Trio {
                 first: zero.clone(),
                 second: zero.clone(),
                 third: zero.clone(),
@@ -110,9 +175,9 @@ pub trait EuclideanRing: Clone + std::fmt::Display + PartialEq {
         }
 
         if a != zero && b == zero {
-            let reg = a.regular();
+            let reg = self.regular(&a);
 
-            return Trio::<Self> {
+            return Trio {
                 first: reg.third,
                 second: zero.clone(),
                 third: reg.second,
@@ -120,79 +185,282 @@ pub trait EuclideanRing: Clone + std::fmt::Display + PartialEq {
         }
 
         if a == zero && b != zero {
-            let reg = b.regular();
+            let reg = self.regular(&b);
 
-            return Trio::<Self> {
+            return Trio {
                 first: zero.clone(),
                 second: reg.third,
                 third: reg.second,
             }
         }
 
-        let max_step: usize = std::cmp::max(a.norm(), b.norm()) + 1;
-        let mut vec_q: Vec<Self> = vec![zero.clone(); max_step+1];
+        // quotients are pushed as they come; vec_q[0] is only a placeholder.
+        // (the norm of a big integer is no bound we can afford to allocate.)
+        let mut vec_q: Vec<Self::Elem> = vec![zero.clone()];
 
-        let mut  mod_temp = Self::divmod(&a, &b);
+        let mut mod_temp = self.divmod(&a, &b);
         let mut q = mod_temp.first; let mut r = mod_temp.second;
 
         // edge case
         if r == zero {
-            return Trio::<Self> {
+            return Trio {
                 first: zero.clone(),
                 second: one.clone(),
                 third: b.clone(),
             }
         }
 
-        let mut done: bool = { Self::divmod(&b, &r).second == zero };
+        let mut done: bool = { self.divmod(&b, &r).second == zero };
 
         let mut i: usize = 1;
-        vec_q[i] = q;
+        vec_q.push(q);
 
         while !done {
             a = b.clone();
             b = r.clone();
-            
-            mod_temp = Self::divmod(&a, &b);
+
+            mod_temp = self.divmod(&a, &b);
             q = mod_temp.first;
             r = mod_temp.second;
             i += 1;
-            vec_q[i] = q;
-
-            done = Self::divmod(&b, &r).second == zero;  
+            vec_q.push(q);
 
+            done = self.divmod(&b, &r).second == zero;
         }
 
-        let mut duo = Duo::<Self>{first: one.clone(), second: Self::neg(&vec_q[i].clone())};
-        
+        let mut duo = Duo { first: one.clone(), second: self.neg(&vec_q[i]) };
+
         while i > 1 {
-            let trio = Trio::<Self>{
+            let trio = Trio {
                 first: duo.first,
-                second: duo.second, 
+                second: duo.second,
                 third: vec_q[i-1].clone(),
             };
-            
-            duo = Self::recover(&trio);
+
+            duo = self.recover(&trio);
             i -= 1;
         }
 
-        let reg_r = r.regular();
+        let reg_r = self.regular(&r);
         let monicifier = reg_r.third;
 
-        Trio::<Self>{
-            first: Self::multiply(&duo.first, &monicifier),
-            second: Self::multiply(&duo.second, &monicifier),
+        Trio {
+            first: self.multiply(&duo.first, &monicifier),
+            second: self.multiply(&duo.second, &monicifier),
             third: reg_r.second,
         }
     }
 
-    fn unwrapped_bezout(f: &Self, g: &Self) -> (Self, Self, Self) {
-        let res = Self::bezout(&Duo {
+    // bezout for any number of inputs: returns (U_1..U_n, H), U_1 F_1 + ... + U_n F_n = H,
+    // with H the regular gcd of all F_k. It folds bezout from the left, so for two inputs
+    // the coefficients are those of bezout.
+    fn bezout_all(&self, inputs: &[Self::Elem]) -> (Vec<Self::Elem>, Self::Elem) {
+        let Some((first, rest)) = inputs.split_first() else {
+            return (Vec::new(), self.zero());
+        };
+
+        let mut coeffs: Vec<Self::Elem> = vec![self.one()];
+        let mut h = first.clone();
+        for f in rest {
+            let (u, v, g) = self.unwrapped_bezout(&h, f);
+            coeffs = coeffs.iter().map(|c| self.multiply(c, &u)).collect();
+            coeffs.push(v);
+            h = g;
+        }
+
+        // bezout leaves H as it is when one input divides the other.
+        let reg = self.regular(&h);
+        if reg.second == self.zero() {
+            return (coeffs, h);
+        }
+        coeffs = coeffs.iter().map(|c| self.multiply(c, &reg.third)).collect();
+
+        (coeffs, reg.second)
+    }
+
+    // the regular lcm; zero if x or y is.
+    fn lcm(&self, x: &Self::Elem, y: &Self::Elem) -> Self::Elem {
+        if *x == self.zero() || *y == self.zero() {
+            return self.zero();
+        }
+
+        let (_, _, g) = self.unwrapped_bezout(x, y);
+        self.regular(&self.multiply(x, &self.divmod(y, &g).first)).second
+    }
+
+    // the solutions of a X + b Y = c, for a and b not both zero: X = x + s T and Y = y - r T
+    // for any T, with s = b / g, r = a / g and g the gcd, up to the unit that makes s regular.
+    // x is reduced mod s, so that it is the smallest solution, unless s is zero.
+    //
+    // Err(g) if g does not divide c.
+    fn solve_linear(&self, a: &Self::Elem, b: &Self::Elem, c: &Self::Elem) -> Result<LinearSolution<Self::Elem>, Self::Elem> {
+        let zero = self.zero();
+        let (coeffs, g) = self.bezout_all(&[a.clone(), b.clone()]);
+        let duo = self.divmod(c, &g);
+        if duo.second != zero {
+            return Err(g);
+        }
+
+        let mut step_x = self.divmod(b, &g).first;
+        let mut step_y = self.divmod(a, &g).first;
+        let mut x = self.multiply(&coeffs[0], &duo.first);
+        let mut y = self.multiply(&coeffs[1], &duo.first);
+        if step_x != zero {
+            let reg = self.regular(&step_x);
+            step_x = reg.second;
+            step_y = self.multiply(&step_y, &reg.third);
+
+            // T = -q for x = q s + (x mod s).
+            let q = self.divmod(&x, &step_x).first;
+            x = self.subtract(&x, &self.multiply(&q, &step_x));
+            y = self.add(&y, &self.multiply(&q, &step_y));
+        }
+
+        Ok(LinearSolution { x, y, step_x, step_y })
+    }
+
+    // the extended Euclidean algorithm on (F, G), one step per division until the remainder
+    // is zero. The last nonzero remainder is the gcd up to a unit, and its s and t give
+    // the coefficients of bezout before they are made regular.
+    fn euclid_trace(&self, input: &Duo<Self::Elem>) -> Vec<EuclidStep<Self::Elem>> {
+        let zero = self.zero();
+        let mut steps: Vec<EuclidStep<Self::Elem>> = Vec::new();
+
+        let mut a = input.first.clone();
+        let mut b = input.second.clone();
+        let (mut s_a, mut s_b) = (self.one(), zero.clone());
+        let (mut t_a, mut t_b) = (zero.clone(), self.one());
+
+        while b != zero {
+            let duo = self.divmod(&a, &b);
+            let s = self.subtract(&s_a, &self.multiply(&duo.first, &s_b));
+            let t = self.subtract(&t_a, &self.multiply(&duo.first, &t_b));
+
+            steps.push(EuclidStep {
+                dividend: a,
+                divisor: b.clone(),
+                quotient: duo.first,
+                remainder: duo.second.clone(),
+                s: s.clone(),
+                t: t.clone(),
+            });
+
+            a = b; b = duo.second;
+            s_a = s_b; s_b = s;
+            t_a = t_b; t_b = t;
+        }
+
+        steps
+    }
+
+    fn unwrapped_bezout(&self, f: &Self::Elem, g: &Self::Elem) -> (Self::Elem, Self::Elem, Self::Elem) {
+        let res = self.bezout(&Duo {
             first: f.clone(), second: g.clone()
         });
 
         (res.first, res.second, res.third)
     }
+
+    // Chinese remainder theorem: solves X = a_k mod m_k for all pairs (a_k, m_k), m_k nonzero.
+    // The moduli need not be coprime. Returns (X, M), where M is the regular lcm of the m_k
+    // and X is reduced mod M.
+    //
+    // Err((k, g)) if the k-th congruence contradicts the previous ones: their combined
+    // modulus M and m_k have gcd g, and the residues differ mod g.
+    fn crt(&self, pairs: &[Duo<Self::Elem>]) -> Result<Duo<Self::Elem>, (usize, Self::Elem)> {
+        let mut x = self.zero();
+        let mut m = self.one();
+
+        for (k, pair) in pairs.iter().enumerate() {
+            // X = x + M t, where M t = a_k - x mod m_k. With U M + V m_k = g,
+            // t = U (a_k - x) / g works, as long as g divides a_k - x.
+            let (u, _, g) = self.unwrapped_bezout(&m, &pair.second);
+            let duo = self.divmod(&self.subtract(&pair.first, &x), &g);
+            if duo.second != self.zero() {
+                return Err((k, g));
+            }
+
+            let t = self.multiply(&u, &duo.first);
+            x = self.add(&x, &self.multiply(&m, &t));
+            m = self.regular(&self.multiply(&m, &self.divmod(&pair.second, &g).first)).second;
+            x = self.divmod(&x, &m).second;
+        }
+
+        Ok(Duo {
+            first: x,
+            second: m,
+        })
+    }
+}
+
+// a EuclideanRing as a EuclideanDomain. The type is the ring, so there is nothing to hold.
+pub struct RingOf<R>(PhantomData<R>);
+
+impl<R> Default for RingOf<R> {
+    fn default() -> Self {
+        RingOf(PhantomData)
+    }
+}
+
+impl<R: EuclideanRing> EuclideanDomain for RingOf<R> {
+    type Elem = R;
+
+    fn zero(&self) -> R {
+        R::zero()
+    }
+
+    fn one(&self) -> R {
+        R::one()
+    }
+
+    fn add(&self, x: &R, y: &R) -> R {
+        R::add(x, y)
+    }
+
+    fn subtract(&self, x: &R, y: &R) -> R {
+        R::subtract(x, y)
+    }
+
+    fn multiply(&self, x: &R, y: &R) -> R {
+        R::multiply(x, y)
+    }
+
+    fn neg(&self, x: &R) -> R {
+        x.neg()
+    }
+
+    fn try_divmod(&self, x: &R, y: &R) -> Option<Duo<R>> {
+        R::try_divmod(x, y)
+    }
+
+    fn divmod(&self, x: &R, y: &R) -> Duo<R> {
+        R::divmod(x, y)
+    }
+
+    fn regular(&self, x: &R) -> Trio<R> {
+        x.regular()
+    }
+}
+
+// a step of the extended Euclidean algorithm on (F, G):
+// dividend = quotient * divisor + remainder, and remainder = s * F + t * G.
+#[derive(Clone, Debug)]
+pub struct EuclidStep<T> {
+    pub dividend: T,
+    pub divisor: T,
+    pub quotient: T,
+    pub remainder: T,
+    pub s: T,
+    pub t: T,
+}
+
+// the solutions of a X + b Y = c: X = x + step_x T and Y = y - step_y T, T arbitrary.
+#[derive(Clone, Debug)]
+pub struct LinearSolution<T> {
+    pub x: T,
+    pub y: T,
+    pub step_x: T,
+    pub step_y: T,
 }
 
 pub trait Ring: Clone + PartialEq {
@@ -204,3 +472,179 @@ pub trait Ring: Clone + PartialEq {
     fn neg(&self) -> Self;
     fn equal(x: &Self, y: &Self) -> bool;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arithmetic::{int, Integer, GaussInteger, ZPol, QPol};
+
+    fn pairs(input: &[(int, int)]) -> Vec<Duo<Integer>> {
+        input.iter().map(|&(a, m)| Duo { first: Integer::new(a), second: Integer::new(m) }).collect()
+    }
+
+    fn zpol(coeffs: &[int]) -> ZPol {
+        ZPol { vector: coeffs.iter().map(|&c| Integer::new(c)).collect() }
+    }
+
+    #[test]
+    fn crt_coprime_moduli() {
+        let res = Integer::crt(&pairs(&[(2, 3), (3, 5), (2, 7)])).ok().unwrap();
+        assert_eq!(res.first, Integer::new(23));
+        assert_eq!(res.second, Integer::new(105));
+
+        // moduli are taken up to sign, and residues reduced.
+        let res = Integer::crt(&pairs(&[(-1, -4), (7, 9)])).ok().unwrap();
+        assert_eq!(res.first, Integer::new(7));
+        assert_eq!(res.second, Integer::new(36));
+    }
+
+    #[test]
+    fn crt_non_coprime_moduli() {
+        let res = Integer::crt(&pairs(&[(1, 4), (3, 6)])).ok().unwrap();
+        assert_eq!(res.first, Integer::new(9));
+        assert_eq!(res.second, Integer::new(12));
+
+        // 2 mod 6 contradicts 1 mod 4 mod their gcd 2.
+        let Err((k, g)) = Integer::crt(&pairs(&[(5, 7), (1, 4), (2, 6)])) else {
+            panic!("the system is inconsistent.");
+        };
+        assert_eq!(k, 2);
+        assert_eq!(g, Integer::new(2));
+    }
+
+    #[test]
+    fn crt_polynomials() {
+        // X = 1 mod x - 1 and X = 4 mod x - 2 give X = 3x - 2 mod x^2 - 3x + 2.
+        let res = QPol::crt(&[
+            Duo { first: zpol(&[1]).to_qpol(), second: zpol(&[-1, 1]).to_qpol() },
+            Duo { first: zpol(&[4]).to_qpol(), second: zpol(&[-2, 1]).to_qpol() },
+        ]).ok().unwrap();
+
+        assert_eq!(res.first, zpol(&[-2, 3]).to_qpol());
+        assert_eq!(res.second, zpol(&[2, -3, 1]).to_qpol());
+    }
+
+    // a (x + s t) + b (y - r t) = c for a few t.
+    fn assert_solves<R: EuclideanRing + std::fmt::Debug>(a: &R, b: &R, c: &R, sol: &LinearSolution<R>) {
+        let mut t = R::zero();
+        let mut k = 0;
+        while k < 3 {
+            let x = R::add(&sol.x, &R::multiply(&sol.step_x, &t));
+            let y = R::subtract(&sol.y, &R::multiply(&sol.step_y, &t));
+            assert_eq!(R::add(&R::multiply(a, &x), &R::multiply(b, &y)), *c);
+            t = R::add(&t, &R::one());
+            k += 1;
+        }
+    }
+
+    #[test]
+    fn solve_linear_integers() {
+        let (a, b, c) = (Integer::new(12), Integer::new(-18), Integer::new(30));
+        let sol = Integer::solve_linear(&a, &b, &c).ok().unwrap();
+        assert_solves(&a, &b, &c, &sol);
+
+        // s = b / g is made positive, and x is the smallest solution.
+        assert_eq!(sol.step_x, Integer::new(3));
+        assert_eq!(sol.step_y, Integer::new(-2));
+        assert_eq!(sol.x, Integer::new(1));
+        assert_eq!(sol.y, Integer::new(-1));
+    }
+
+    #[test]
+    fn solve_linear_degenerate() {
+        // with b = 0, X is determined and Y arbitrary.
+        let (a, b, c) = (Integer::new(4), Integer::zero(), Integer::new(8));
+        let sol = Integer::solve_linear(&a, &b, &c).ok().unwrap();
+        assert_eq!(sol.x, Integer::new(2));
+        assert_eq!(sol.step_x, Integer::zero());
+        assert_solves(&a, &b, &c, &sol);
+
+        // gcd(4, 6) = 2 does not divide 3.
+        let Err(g) = Integer::solve_linear(&Integer::new(4), &Integer::new(6), &Integer::new(3)) else {
+            panic!("2 does not divide 3.");
+        };
+        assert_eq!(g, Integer::new(2));
+    }
+
+    #[test]
+    fn solve_linear_gauss_and_polynomials() {
+        let (a, b, c) = (GaussInteger::new(3, 1), GaussInteger::new(1, -2), GaussInteger::new(5, 5));
+        let sol = GaussInteger::solve_linear(&a, &b, &c).ok().unwrap();
+        assert_solves(&a, &b, &c, &sol);
+
+        let (a, b, c) = (zpol(&[-1, 0, 1]).to_qpol(), zpol(&[1, 1]).to_qpol(), zpol(&[2, 3, 1]).to_qpol());
+        let sol = QPol::solve_linear(&a, &b, &c).ok().unwrap();
+        assert_solves(&a, &b, &c, &sol);
+        // s = b / g is a unit, so x mod s is 0.
+        assert_eq!(sol.x, QPol::zero());
+
+        // x^2 - 1 and x + 1 have gcd x + 1, which does not divide x.
+        assert!(QPol::solve_linear(&a, &b, &zpol(&[0, 1]).to_qpol()).is_err());
+    }
+
+    // every row is a division, and its remainder is s F + t G.
+    fn assert_trace<R: EuclideanRing + std::fmt::Debug>(f: &R, g: &R, rows: usize) {
+        let steps = R::euclid_trace(&Duo { first: f.clone(), second: g.clone() });
+        assert_eq!(steps.len(), rows);
+        for step in &steps {
+            assert_eq!(R::add(&R::multiply(&step.quotient, &step.divisor), &step.remainder), step.dividend);
+            assert_eq!(R::add(&R::multiply(&step.s, f), &R::multiply(&step.t, g)), step.remainder);
+        }
+
+        // the last remainder is zero, and the last divisor the gcd up to a unit.
+        let last = steps.last().unwrap();
+        assert_eq!(last.remainder, R::zero());
+        assert_eq!(last.divisor.regular().second, R::gcd(&Duo { first: f.clone(), second: g.clone() }).regular().second);
+    }
+
+    #[test]
+    fn euclid_trace_rows() {
+        assert_trace(&Integer::new(240), &Integer::new(46), 5);
+        assert_trace(&Integer::new(-7), &Integer::new(3), 3);
+        assert_trace(&GaussInteger::new(11, 3), &GaussInteger::new(1, 8), 3);
+        assert_trace(&zpol(&[-1, 0, 0, 1]).to_qpol(), &zpol(&[-1, 0, 1]).to_qpol(), 2);
+
+        // no division by zero: the trace of (F, 0) is empty.
+        let steps = Integer::euclid_trace(&Duo { first: Integer::new(5), second: Integer::zero() });
+        assert!(steps.is_empty());
+    }
+
+    // U_1 F_1 + ... + U_n F_n = H, and H divides every F_k.
+    fn assert_bezout_all<R: EuclideanRing + std::fmt::Debug>(inputs: &[R], gcd: &R) {
+        let (coeffs, h) = R::bezout_all(inputs);
+        assert_eq!(coeffs.len(), inputs.len());
+        let mut sum = R::zero();
+        for (u, f) in coeffs.iter().zip(inputs) {
+            sum = R::add(&sum, &R::multiply(u, f));
+        }
+        assert_eq!(sum, h);
+        for f in inputs {
+            assert_eq!(R::divmod(f, &h).second, R::zero());
+        }
+        assert_eq!(h, *gcd);
+    }
+
+    #[test]
+    fn bezout_all_many_inputs() {
+        let ints = |xs: &[int]| xs.iter().map(|&x| Integer::new(x)).collect::<Vec<_>>();
+        assert_bezout_all(&ints(&[12, 18, 27]), &Integer::new(3));
+        assert_bezout_all(&ints(&[6, 10, 15]), &Integer::new(1));
+        assert_bezout_all(&ints(&[-4, 6, -10, 0]), &Integer::new(2));
+        assert_bezout_all(&ints(&[0, 35]), &Integer::new(35));
+
+        let gauss = [GaussInteger::new(3, 1), GaussInteger::new(2, 0), GaussInteger::new(1, 1)];
+        let (_, h) = GaussInteger::bezout_all(&gauss);
+        assert_bezout_all(&gauss, &h);
+        assert_eq!(h.regular().second, GaussInteger::new(1, 1).regular().second);
+
+        // x^2 - 1, x^2 + x and x^3 - x have gcd x + 1, made monic.
+        let pols = [zpol(&[-1, 0, 1]).to_qpol(), zpol(&[0, 1, 1]).to_qpol(), zpol(&[0, -1, 0, 1]).to_qpol()];
+        assert_bezout_all(&pols, &zpol(&[1, 1]).to_qpol());
+
+        // for two inputs, the coefficients are those of bezout.
+        let (coeffs, h) = Integer::bezout_all(&ints(&[240, 46]));
+        let trio = Integer::bezout(&Duo { first: Integer::new(240), second: Integer::new(46) });
+        assert_eq!((coeffs[0].clone(), coeffs[1].clone(), h), (trio.first, trio.second, trio.third));
+    }
+}
+
@@ -1,4 +1,5 @@
 use super::*;
+use num_traits::{Signed, One, Zero};
 
 impl<T: std::fmt::Display> std::fmt::Display for Duo<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -21,55 +22,55 @@ pub trait SignDisplay {
 
 impl SignDisplay for Rational {
     fn signed_display(&self) -> String {
-        let reduced = self.to_pair().to_rational();
+        let reduced = self.reduced();
         let num = reduced.numerator;
         let den = reduced.denominator;
 
         // special treatment to 0
-        if num == 0 {
-            return format!("0");
+        if num.is_zero() {
+            return "0".to_string();
         }
 
         // general 
-        if num < 0 {
-            if den == 1 {
-                return format!("- {}", -num);
+        if num.is_negative() {
+            if den.is_one() {
+                format!("- {}", -&num)
             } else {
-                return format!("- ({}/{})", -num, den);
+                format!("- ({}/{})", -&num, den)
             }
         }
         else {
-            if den == 1 {
-                return format!("+ {}", num);
+            if den.is_one() {
+                format!("+ {}", num)
             } else {
-                return format!("+ ({}/{})", num, den);
+                format!("+ ({}/{})", num, den)
             }
         }
     }
 
     fn semi_signed_display(&self) -> String {
-        let reduced = self.to_pair().to_rational();
+        let reduced = self.reduced();
         let num = reduced.numerator;
         let den = reduced.denominator;
 
         // edge case: 0
-        if num == 0 {
-            return format!("0");
+        if num.is_zero() {
+            return "0".to_string();
         }
 
         // general case
-        if num < 0 {
-            if den == 1 {
-                return format!("- {}", -num);
+        if num.is_negative() {
+            if den.is_one() {
+                format!("- {}", -&num)
             } else {
-                return format!("- ({}/{})", -num, den);
+                format!("- ({}/{})", -&num, den)
             }
         }
         else {
-            if den == 1 {
-                return format!("{}", num);
+            if den.is_one() {
+                format!("{}", num)
             } else {
-                return format!("({}/{})", num, den);
+                format!("({}/{})", num, den)
             }
         }
     }
@@ -95,67 +96,67 @@ pub trait PolCoeffDisplay {
 
 impl PolCoeffDisplay for Rational {
     fn pol_signed_display(&self) -> String {
-        let reduced = self.to_pair().to_rational();
+        let reduced = self.reduced();
         let num = reduced.numerator;
         let den = reduced.denominator;
 
         // special treatment to 0, 1, -1
-        if num == 0 {
-            return format!("");
+        if num.is_zero() {
+            return String::new();
         }
-        if num == 1 && den == 1 {
-            return format!("+ ");
+        if num.is_one() && den.is_one() {
+            return "+ ".to_string();
         }
-        if num == -1 && den == 1 {
-            return format!("- ")
+        if (-&num).is_one() && den.is_one() {
+            return "- ".to_string()
         }
         
         // general 
-        if num < 0 {
-            if den == 1 {
-                return format!("- {}", -num);
+        if num.is_negative() {
+            if den.is_one() {
+                format!("- {}", -&num)
             } else {
-                return format!("- ({}/{})", -num, den);
+                format!("- ({}/{})", -&num, den)
             }
         }
         else {
-            if den == 1 {
-                return format!("+ {}", num);
+            if den.is_one() {
+                format!("+ {}", num)
             } else {
-                return format!("+ ({}/{})", num, den);
+                format!("+ ({}/{})", num, den)
             }
         }
     }
 
     fn pol_semi_signed_display(&self) -> String {
-        let reduced = self.to_pair().to_rational();
+        let reduced = self.reduced();
         let num = reduced.numerator;
         let den = reduced.denominator;
 
         // edge case: 0,1, -1
-        if num == 0 {
-            return format!("");
+        if num.is_zero() {
+            return String::new();
         }
-        if num == 1 && den == 1 {
-            return format!("");
+        if num.is_one() && den.is_one() {
+            return String::new();
         }
-        if num == -1 && den == 1 {
-            return format!("- ")
+        if (-&num).is_one() && den.is_one() {
+            return "- ".to_string()
         }
 
         // general case
-        if num < 0 {
-            if den == 1 {
-                return format!("- {}", -num);
+        if num.is_negative() {
+            if den.is_one() {
+                format!("- {}", -&num)
             } else {
-                return format!("- ({}/{})", -num, den);
+                format!("- ({}/{})", -&num, den)
             }
         }
         else {
-            if den == 1 {
-                return format!("{}", num);
+            if den.is_one() {
+                format!("{}", num)
             } else {
-                return format!("({}/{})", num, den);
+                format!("({}/{})", num, den)
             }
         }
     }
@@ -182,9 +183,9 @@ impl SimpleDisplay for Integer {
 
 impl SimpleDisplay for Rational {
     fn simple_display(&self) -> String {
-        let reduced = self.to_pair().to_rational();
+        let reduced = self.reduced();
 
-        if reduced.denominator == 1 {
+        if reduced.denominator.is_one() {
             format!("{}", reduced.numerator)
         } else {
             format!("{}/{}", reduced.numerator, reduced.denominator)
@@ -203,7 +204,7 @@ impl SimpleDisplay for Polynomial<Rational> {
 
         // special case
         if order == 0 {
-            return format!("{}", vec[0].semi_signed_display());
+            return vec[0].semi_signed_display().to_string();
         }
         if order == 1 && vec[0] == zero {
             return format!("{}x", vec[1].pol_semi_signed_display());
@@ -240,7 +241,7 @@ impl SimpleDisplay for Polynomial<Rational> {
 
         // constant term
         if vec[i] != zero {
-            let str = format!("{}", vec[i].signed_display());
+            let str = vec[i].signed_display().to_string();
             output = format!("{output} {str}");
         }
 
@@ -268,7 +269,7 @@ impl SimpleDisplay for ComplexRational {
 
         // self = i, 2i, -3i, (5/6)i, -(7/9)i
         else if self.is_pure_imag() {
-            let b = self.imag;
+            let b = &self.imag;
             let res = format!("{} i", b.pol_semi_signed_display());
             res.split_ascii_whitespace().collect()
         }
@@ -288,6 +289,44 @@ impl SimpleDisplay for GaussInteger {
 }
 
 
+// eg. "3 * (x + 1)^2 * x^3". A factor is wrapped in parentheses unless it is a single
+// term, and the unit is dropped when it is 1 (unless nothing else is left).
+impl<T: SimpleDisplay> SimpleDisplay for Factorization<T> {
+    fn simple_display(&self) -> String {
+        let mut terms: Vec<String> = Vec::new();
+
+        // a unit of 1 or -1 is written as a sign only, unless there is nothing else.
+        let unit = self.unit.simple_display();
+        let is_minus_one = unit == "-1" || unit == "- 1";
+        if self.factors.is_empty() || unit != "1" && !is_minus_one {
+            terms.push(unit);
+        }
+
+        for (factor, expon) in &self.factors {
+            let str = factor.simple_display();
+            let is_single_term = !str.chars().skip(1).any(|c| c == ' ' || c == '+' || c == '-');
+            let base = if is_single_term || *expon == 1 && self.factors.len() == 1 && terms.is_empty() {
+                str
+            } else {
+                format!("({str})")
+            };
+
+            if *expon == 1 {
+                terms.push(base);
+            } else {
+                terms.push(format!("{base}^{expon}"));
+            }
+        }
+
+        let product = terms.join(" * ");
+        if is_minus_one && !self.factors.is_empty() {
+            format!("- {product}")
+        } else {
+            product
+        }
+    }
+}
+
 // Chore: Wrapping a fmt::Display around SimpleDisplay
 impl<T: Field> std::fmt::Display for Polynomial<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
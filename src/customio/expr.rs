@@ -0,0 +1,766 @@
+use super::*;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use std::collections::BTreeMap;
+
+// expressions like "(x^2 + 1) * (x - 3) % (x + 2)". The grammar, loosest first:
+//
+//   sum     = product (("+" | "-") product)*
+//   product = unary (("*" | "/" | "%") unary | unary)*
+//   unary   = "-" unary | "+" unary | power
+//   power   = primary ("^" unary)?
+//   primary = number | name | "(" sum ")"
+//
+// A name is either an identifier, or a reference to an earlier result like $3 or $3.U.
+// Juxtaposition is multiplication, at the same level as "*", so that "1/2x" still
+// reads as (1/2) * x, but two numbers in a row like "12 345" are refused. "^" is right associative and binds tighter than unary minus:
+// -x^2 = -(x^2), while 2^-1 is allowed.
+//
+// A sum or a product is kept as a flat chain, so that 1 + 1 + ... + 1 is evaluated by a loop.
+// Only parentheses, signs and exponents nest, and parsing and evaluation recurse along them,
+// so their depth is limited to MAX_NESTING.
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Number(BigInt),
+    Name(String),
+    Plus, Minus, Star, Slash, Percent, Caret,
+    LParen, RParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    if !input.is_ascii() {
+        return Err(ParseError::NotAscii);
+    }
+
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut i: usize = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let digits: String = chars[start..i].iter().collect();
+            tokens.push(Token::Number(digits.parse().unwrap()));
+            continue;
+        }
+
+        if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Name(chars[start..i].iter().collect()));
+            continue;
+        }
+
+        // references to earlier results: $3, or a component like $3.U.
+        if c == '$' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            if i == start + 1 {
+                return Err(ParseError::Operand);
+            }
+            // a component: a letter, then digits for the U_k of a bezout.
+            if i + 1 < chars.len() && chars[i] == '.' && chars[i + 1].is_ascii_alphabetic() {
+                i += 2;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            let name: String = chars[start..i].iter().collect();
+            tokens.push(Token::Name(name.to_ascii_uppercase()));
+            continue;
+        }
+
+        let token = match c {
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '^' => Token::Caret,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            _ => return Err(ParseError::Operand),
+        };
+        tokens.push(token);
+        i += 1;
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum BinOp {
+    Add, Sub, Mul, Div, Rem,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Expr {
+    Number(BigInt),
+    Name(String),
+    Neg(Box<Expr>),
+    // the first operand, then each operator with the operand on its right.
+    Chain(Box<Expr>, Vec<(BinOp, Expr)>),
+    Power(Box<Expr>, Box<Expr>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn sum(&mut self) -> Result<Expr, ParseError> {
+        let first = self.product()?;
+        let mut rest: Vec<(BinOp, Expr)> = Vec::new();
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::Plus) => BinOp::Add,
+                Some(Token::Minus) => BinOp::Sub,
+                _ => return Ok(chain(first, rest)),
+            };
+            self.pos += 1;
+            rest.push((op, self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr, ParseError> {
+        let first = self.unary()?;
+        let mut rest: Vec<(BinOp, Expr)> = Vec::new();
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::Star) => BinOp::Mul,
+                Some(Token::Slash) => BinOp::Div,
+                Some(Token::Percent) => BinOp::Rem,
+                // a number right after a number, as in 12 345, is more likely a typo than a product.
+                Some(Token::Number(_)) if matches!(self.tokens[self.pos - 1], Token::Number(_)) => {
+                    return Err(ParseError::Operand);
+                }
+                // juxtaposition, as in 2x or (x+1)(x-1).
+                Some(Token::Number(_) | Token::Name(_) | Token::LParen) => {
+                    rest.push((BinOp::Mul, self.unary()?));
+                    continue;
+                }
+                _ => return Ok(chain(first, rest)),
+            };
+            self.pos += 1;
+            rest.push((op, self.unary()?));
+        }
+    }
+
+    // every recursion of the parser passes through here.
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING {
+            return Err(ParseError::Nesting);
+        }
+
+        let res = match self.peek() {
+            Some(Token::Minus) => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            Some(Token::Plus) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        };
+
+        self.depth -= 1;
+        res
+    }
+
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let base = self.primary()?;
+        if self.peek() != Some(&Token::Caret) {
+            return Ok(base);
+        }
+
+        self.pos += 1;
+        let expon = self.unary()?;
+        Ok(Expr::Power(Box::new(base), Box::new(expon)))
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Name(name)) => Ok(Expr::Name(name)),
+            Some(Token::LParen) => {
+                let inner = self.sum()?;
+                if self.next() != Some(Token::RParen) {
+                    return Err(ParseError::Operand);
+                }
+                Ok(inner)
+            }
+            _ => Err(ParseError::Operand),
+        }
+    }
+}
+
+fn chain(first: Expr, rest: Vec<(BinOp, Expr)>) -> Expr {
+    if rest.is_empty() {
+        return first;
+    }
+
+    Expr::Chain(Box::new(first), rest)
+}
+
+fn parse_expr(input: &str) -> Result<Expr, ParseError> {
+    let mut parser = Parser { tokens: tokenize(input)?, pos: 0, depth: 0 };
+    let expr = parser.sum()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(ParseError::Operand);
+    }
+
+    Ok(expr)
+}
+
+// an algebra in which expressions are evaluated. Operations that may fail report
+// the failure as a ParseError, since they happen while reading an operand.
+pub trait Evaluator {
+    type Value: Clone;
+
+    fn constant(&self, n: &BigInt) -> Self::Value;
+    // the value of a name, eg. x for polynomials or i for Gaussian integers.
+    fn variable(&self, name: &str) -> Option<Self::Value>;
+
+    fn add(&self, x: &Self::Value, y: &Self::Value) -> Self::Value;
+    fn subtract(&self, x: &Self::Value, y: &Self::Value) -> Self::Value;
+    fn multiply(&self, x: &Self::Value, y: &Self::Value) -> Self::Value;
+    fn neg(&self, x: &Self::Value) -> Self::Value;
+
+    // field division, or exact division in a ring.
+    fn divide(&self, x: &Self::Value, y: &Self::Value) -> Result<Self::Value, ParseError>;
+    // the remainder of euclidean division. Fields have none.
+    fn remainder(&self, _x: &Self::Value, _y: &Self::Value) -> Result<Self::Value, ParseError> {
+        Err(ParseError::Remainder)
+    }
+
+    // the size of x in bits, from which power estimates the size of x^e before computing it.
+    // 0 or 1 means that powers of x do not grow, which is the default: elements of a finite
+    // ring never do, and the degree of polynomials is kept in check by check().
+    fn bits(&self, _x: &Self::Value) -> u64 {
+        0
+    }
+
+    // likewise the degree of a polynomial, 0 for anything else.
+    fn degree(&self, _x: &Self::Value) -> usize {
+        0
+    }
+
+    // called on every intermediate value, eg. to keep polynomial degrees in check.
+    fn check(&self, _x: &Self::Value) -> Result<(), ParseError> {
+        Ok(())
+    }
+}
+
+// names bound by the user, each to an expression. The expressions themselves are
+// evaluated without a scope, so they must not refer to other bindings.
+pub type Scope = BTreeMap<String, String>;
+
+pub fn evaluate<E: Evaluator>(ev: &E, input: &str, scope: &Scope) -> Result<E::Value, ParseError> {
+    let expr = parse_expr(input)?;
+
+    eval_expr(ev, &expr, scope)
+}
+
+fn eval_expr<E: Evaluator>(ev: &E, expr: &Expr, scope: &Scope) -> Result<E::Value, ParseError> {
+    let res = match expr {
+        Expr::Number(n) => ev.constant(n),
+        // the names of the algebra itself, like x, come first.
+        Expr::Name(name) => match (ev.variable(name), scope.get(name)) {
+            (Some(value), _) => value,
+            (None, Some(bound)) => evaluate(ev, bound, &Scope::new())?,
+            (None, None) => return Err(ParseError::Unknown(name.clone())),
+        },
+        Expr::Neg(inner) => ev.neg(&eval_expr(ev, inner, scope)?),
+        Expr::Chain(first, rest) => {
+            let mut x = eval_expr(ev, first, scope)?;
+            for (op, rhs) in rest {
+                let y = eval_expr(ev, rhs, scope)?;
+                x = match op {
+                    BinOp::Add => ev.add(&x, &y),
+                    BinOp::Sub => ev.subtract(&x, &y),
+                    BinOp::Mul => ev.multiply(&x, &y),
+                    BinOp::Div => ev.divide(&x, &y)?,
+                    BinOp::Rem => ev.remainder(&x, &y)?,
+                };
+                ev.check(&x)?;
+            }
+            x
+        }
+        Expr::Power(base, expon) => {
+            let x = eval_expr(ev, base, scope)?;
+            let e = eval_exponent(expon, scope)?;
+            power(ev, &x, &e)?
+        }
+    };
+
+    ev.check(&res)?;
+    Ok(res)
+}
+
+// exponents are integers whatever the algebra, so they are read in Z, names included.
+// A name bound to something else, like 1/2 in Q or x + 1 in QPol, is refused as such.
+fn eval_exponent(expon: &Expr, scope: &Scope) -> Result<BigInt, ParseError> {
+    let mut names: Vec<&str> = Vec::new();
+    collect_names(expon, &mut names);
+    for name in names {
+        if let Some(bound) = scope.get(name) && evaluate(&Integers, bound, &Scope::new()).is_err() {
+            return Err(ParseError::Exponent(name.to_string()));
+        }
+    }
+
+    Ok(eval_expr(&Integers, expon, scope)?.number)
+}
+
+fn collect_names<'a>(expr: &'a Expr, names: &mut Vec<&'a str>) {
+    match expr {
+        Expr::Number(_) => (),
+        Expr::Name(name) => names.push(name),
+        Expr::Neg(inner) => collect_names(inner, names),
+        Expr::Chain(first, rest) => {
+            collect_names(first, names);
+            for (_, rhs) in rest {
+                collect_names(rhs, names);
+            }
+        }
+        Expr::Power(base, expon) => {
+            collect_names(base, names);
+            collect_names(expon, names);
+        }
+    }
+}
+
+// square and multiply. A negative exponent inverts the base first.
+fn power<E: Evaluator>(ev: &E, x: &E::Value, e: &BigInt) -> Result<E::Value, ParseError> {
+    // x^e has e times the degree of x, and takes about e times its bits.
+    let degree = ev.degree(x);
+    if degree > 0 && e.abs().to_usize().and_then(|e| e.checked_mul(degree)).is_none_or(|d| d > MAX_DEGREE) {
+        return Err(ParseError::Degree);
+    }
+    let bits = ev.bits(x);
+    if bits > 1 && e.abs().to_u64().and_then(|e| e.checked_mul(bits)).is_none_or(|size| size > MAX_BITS) {
+        return Err(ParseError::Size);
+    }
+    // a polynomial has that many coefficients, each growing like a number: (x + 1)^e
+    // has binomial coefficients of up to e bits. Both bounds hold by now, so this cannot overflow.
+    if degree > 0 && bits > 1 {
+        let e = e.abs().to_u64().unwrap_or(u64::MAX);
+        if (e * degree as u64 + 1) * (e * bits) > MAX_POL_BITS {
+            return Err(ParseError::PolSize);
+        }
+    }
+
+    let one = ev.constant(&BigInt::one());
+    let mut base = if e.is_negative() { ev.divide(&one, x)? } else { x.clone() };
+    let mut expon = e.abs();
+    let mut res = one;
+
+    while !expon.is_zero() {
+        if expon.bit(0) {
+            res = ev.multiply(&res, &base);
+            ev.check(&res)?;
+        }
+        expon >>= 1;
+        if !expon.is_zero() {
+            base = ev.multiply(&base, &base);
+            ev.check(&base)?;
+        }
+    }
+
+    Ok(res)
+}
+
+// the algebras without parameters. Parse is implemented on top of them.
+pub(super) struct Integers;
+pub(super) struct Rationals;
+pub(super) struct GaussIntegers;
+pub(super) struct ComplexRationals;
+pub(super) struct RationalPolynomials;
+
+impl Evaluator for Integers {
+    type Value = Integer;
+
+    fn constant(&self, n: &BigInt) -> Integer {
+        Integer::from_bigint(n.clone())
+    }
+
+    fn variable(&self, _name: &str) -> Option<Integer> {
+        None
+    }
+
+    fn add(&self, x: &Integer, y: &Integer) -> Integer {
+        Integer::add(x, y)
+    }
+
+    fn subtract(&self, x: &Integer, y: &Integer) -> Integer {
+        Integer::subtract(x, y)
+    }
+
+    fn multiply(&self, x: &Integer, y: &Integer) -> Integer {
+        Integer::multiply(x, y)
+    }
+
+    fn neg(&self, x: &Integer) -> Integer {
+        x.neg()
+    }
+
+    fn divide(&self, x: &Integer, y: &Integer) -> Result<Integer, ParseError> {
+        exact_divide(x, y)
+    }
+
+    fn remainder(&self, x: &Integer, y: &Integer) -> Result<Integer, ParseError> {
+        euclidean_remainder(x, y)
+    }
+
+    fn bits(&self, x: &Integer) -> u64 {
+        x.number.bits()
+    }
+}
+
+impl Evaluator for GaussIntegers {
+    type Value = GaussInteger;
+
+    fn constant(&self, n: &BigInt) -> GaussInteger {
+        GaussInteger::from_integers(Integer::from_bigint(n.clone()), Integer::zero())
+    }
+
+    fn variable(&self, name: &str) -> Option<GaussInteger> {
+        (name == "i").then(|| GaussInteger::new(0, 1))
+    }
+
+    fn add(&self, x: &GaussInteger, y: &GaussInteger) -> GaussInteger {
+        GaussInteger::add(x, y)
+    }
+
+    fn subtract(&self, x: &GaussInteger, y: &GaussInteger) -> GaussInteger {
+        GaussInteger::subtract(x, y)
+    }
+
+    fn multiply(&self, x: &GaussInteger, y: &GaussInteger) -> GaussInteger {
+        GaussInteger::multiply(x, y)
+    }
+
+    fn neg(&self, x: &GaussInteger) -> GaussInteger {
+        x.neg()
+    }
+
+    fn divide(&self, x: &GaussInteger, y: &GaussInteger) -> Result<GaussInteger, ParseError> {
+        exact_divide(x, y)
+    }
+
+    fn remainder(&self, x: &GaussInteger, y: &GaussInteger) -> Result<GaussInteger, ParseError> {
+        euclidean_remainder(x, y)
+    }
+
+    // those of the norm, which is 1 for the units.
+    fn bits(&self, x: &GaussInteger) -> u64 {
+        let (a, b) = (&x.real.number, &x.imag.number);
+        (a * a + b * b).bits()
+    }
+}
+
+impl Evaluator for RationalPolynomials {
+    type Value = QPol;
+
+    fn constant(&self, n: &BigInt) -> QPol {
+        QPol::as_polynomial(&Rational { numerator: n.clone(), denominator: BigInt::one() })
+    }
+
+    fn variable(&self, name: &str) -> Option<QPol> {
+        (name == "x").then(|| QPol::monomial(&Rational::one(), 1))
+    }
+
+    fn add(&self, x: &QPol, y: &QPol) -> QPol {
+        QPol::add(x, y)
+    }
+
+    fn subtract(&self, x: &QPol, y: &QPol) -> QPol {
+        QPol::subtract(x, y)
+    }
+
+    fn multiply(&self, x: &QPol, y: &QPol) -> QPol {
+        QPol::multiply(x, y)
+    }
+
+    fn neg(&self, x: &QPol) -> QPol {
+        x.neg()
+    }
+
+    fn divide(&self, x: &QPol, y: &QPol) -> Result<QPol, ParseError> {
+        exact_divide(x, y)
+    }
+
+    fn remainder(&self, x: &QPol, y: &QPol) -> Result<QPol, ParseError> {
+        euclidean_remainder(x, y)
+    }
+
+    fn check(&self, x: &QPol) -> Result<(), ParseError> {
+        check_degree(x.norm())
+    }
+
+    // those of the largest coefficient, plus those of the number of terms, which add up
+    // in the coefficients of a power.
+    fn bits(&self, x: &QPol) -> u64 {
+        let terms = x.vector.iter().filter(|c| !c.is_zero()).count() as u64;
+        let term_bits = terms.next_power_of_two().trailing_zeros() as u64;
+        x.vector.iter().map(rational_bits).max().unwrap_or(0) + term_bits
+    }
+
+    fn degree(&self, x: &QPol) -> usize {
+        x.norm()
+    }
+}
+
+impl Evaluator for Rationals {
+    type Value = Rational;
+
+    fn constant(&self, n: &BigInt) -> Rational {
+        Rational { numerator: n.clone(), denominator: BigInt::one() }
+    }
+
+    fn variable(&self, _name: &str) -> Option<Rational> {
+        None
+    }
+
+    fn add(&self, x: &Rational, y: &Rational) -> Rational {
+        Rational::add(x, y)
+    }
+
+    fn subtract(&self, x: &Rational, y: &Rational) -> Rational {
+        Rational::subtract(x, y)
+    }
+
+    fn multiply(&self, x: &Rational, y: &Rational) -> Rational {
+        Rational::multiply(x, y)
+    }
+
+    fn neg(&self, x: &Rational) -> Rational {
+        x.neg()
+    }
+
+    fn divide(&self, x: &Rational, y: &Rational) -> Result<Rational, ParseError> {
+        field_divide(x, y)
+    }
+
+    fn bits(&self, x: &Rational) -> u64 {
+        rational_bits(x)
+    }
+}
+
+impl Evaluator for ComplexRationals {
+    type Value = ComplexRational;
+
+    fn constant(&self, n: &BigInt) -> ComplexRational {
+        ComplexRational {
+            real: Rational { numerator: n.clone(), denominator: BigInt::one() },
+            imag: Rational::zero(),
+        }
+    }
+
+    fn variable(&self, name: &str) -> Option<ComplexRational> {
+        (name == "i").then(|| ComplexRational { real: Rational::zero(), imag: Rational::one() })
+    }
+
+    fn add(&self, x: &ComplexRational, y: &ComplexRational) -> ComplexRational {
+        ComplexRational::add(x, y)
+    }
+
+    fn subtract(&self, x: &ComplexRational, y: &ComplexRational) -> ComplexRational {
+        ComplexRational::subtract(x, y)
+    }
+
+    fn multiply(&self, x: &ComplexRational, y: &ComplexRational) -> ComplexRational {
+        ComplexRational::multiply(x, y)
+    }
+
+    fn neg(&self, x: &ComplexRational) -> ComplexRational {
+        x.neg()
+    }
+
+    fn divide(&self, x: &ComplexRational, y: &ComplexRational) -> Result<ComplexRational, ParseError> {
+        field_divide(x, y)
+    }
+
+    // those of the norm, as for Gaussian integers.
+    fn bits(&self, x: &ComplexRational) -> u64 {
+        let norm = Rational::add(&Rational::multiply(&x.real, &x.real), &Rational::multiply(&x.imag, &x.imag));
+        rational_bits(&norm)
+    }
+}
+
+fn rational_bits(x: &Rational) -> u64 {
+    x.numerator.bits().max(x.denominator.bits())
+}
+
+pub(super) fn check_degree(degree: usize) -> Result<(), ParseError> {
+    if degree > MAX_DEGREE {
+        return Err(ParseError::Degree);
+    }
+
+    Ok(())
+}
+
+fn exact_divide<R: EuclideanRing>(x: &R, y: &R) -> Result<R, ParseError> {
+    let duo = R::try_divmod(x, y).ok_or(ParseError::ZeroDenom)?;
+    if !duo.second.is_zero() {
+        return Err(ParseError::Inexact);
+    }
+
+    Ok(duo.first)
+}
+
+fn euclidean_remainder<R: EuclideanRing>(x: &R, y: &R) -> Result<R, ParseError> {
+    Ok(R::try_divmod(x, y).ok_or(ParseError::ZeroDenom)?.second)
+}
+
+fn field_divide<F: Field>(x: &F, y: &F) -> Result<F, ParseError> {
+    F::divide(x, y).ok_or(ParseError::ZeroDenom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval_z(input: &str) -> Result<Integer, ParseError> {
+        evaluate(&Integers, input, &Scope::new())
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        assert_eq!(eval_z("1 + 2 * 3"), Ok(Integer::new(7)));
+        assert_eq!(eval_z("(1 + 2) * 3"), Ok(Integer::new(9)));
+        assert_eq!(eval_z("10 - 4 - 3"), Ok(Integer::new(3)));
+        assert_eq!(eval_z("2^3^2"), Ok(Integer::new(512)));
+        assert_eq!(eval_z("-2^2"), Ok(Integer::new(-4)));
+        assert_eq!(eval_z("(-2)^2"), Ok(Integer::new(4)));
+        assert_eq!(eval_z("-7 % 3"), Ok(Integer::new(2)));
+        assert_eq!(eval_z("2(3 + 4)"), Ok(Integer::new(14)));
+    }
+
+    #[test]
+    fn juxtaposition_binds_like_product() {
+        let half_x = evaluate(&RationalPolynomials, "1/2x", &Scope::new()).unwrap();
+        assert_eq!(half_x, QPol::monomial(&Rational::new(1, 2).unwrap(), 1));
+
+        let i = evaluate(&GaussIntegers, "(1 + i)(1 - i)", &Scope::new()).unwrap();
+        assert_eq!(i, GaussInteger::new(2, 0));
+
+        // but not for two numbers in a row.
+        assert_eq!(eval_z("12 345"), Err(ParseError::Operand));
+        assert_eq!(eval_z("2^3 4"), Err(ParseError::Operand));
+        assert_eq!(eval_z("2 (3) 4"), Ok(Integer::new(24)));
+    }
+
+    #[test]
+    fn names_from_scope() {
+        let mut scope = Scope::new();
+        scope.insert("n".to_string(), "6 * 7".to_string());
+
+        assert_eq!(evaluate(&Integers, "n + 1", &scope), Ok(Integer::new(43)));
+        assert_eq!(evaluate(&Integers, "m", &scope), Err(ParseError::Unknown("m".to_string())));
+    }
+
+    #[test]
+    fn names_in_exponents_are_integers() {
+        let mut scope = Scope::new();
+        scope.insert("n".to_string(), "3".to_string());
+        scope.insert("h".to_string(), "1/2".to_string());
+
+        assert_eq!(evaluate(&Rationals, "2^n", &scope), Ok(Rational::new(8, 1).unwrap()));
+        assert_eq!(evaluate(&Rationals, "2^(n - h)", &scope), Err(ParseError::Exponent("h".to_string())));
+        assert_eq!(evaluate(&Rationals, "h^2", &scope), Ok(Rational::new(1, 4).unwrap()));
+    }
+
+    #[test]
+    fn malformed_input() {
+        assert_eq!(eval_z("(1 + 2"), Err(ParseError::Operand));
+        assert_eq!(eval_z("1 + 2)"), Err(ParseError::Operand));
+        assert_eq!(eval_z("1 +"), Err(ParseError::Operand));
+        assert_eq!(eval_z("1 & 2"), Err(ParseError::Operand));
+        assert_eq!(eval_z("$"), Err(ParseError::Operand));
+        assert_eq!(eval_z("1 + é"), Err(ParseError::NotAscii));
+    }
+
+    #[test]
+    fn arithmetic_errors() {
+        assert_eq!(eval_z("7 / 2"), Err(ParseError::Inexact));
+        assert_eq!(eval_z("7 / 0"), Err(ParseError::ZeroDenom));
+        assert_eq!(eval_z("7 % 0"), Err(ParseError::ZeroDenom));
+        assert_eq!(eval_z("2^-1"), Err(ParseError::Inexact));
+        assert_eq!(evaluate(&Rationals, "1 % 2", &Scope::new()), Err(ParseError::Remainder));
+    }
+
+    #[test]
+    fn nesting_limit() {
+        let depth = MAX_NESTING - 1;
+        let parens = format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(eval_z(&parens), Ok(Integer::new(1)));
+
+        let too_deep = 30000;
+        let parens = format!("{}1{}", "(".repeat(too_deep), ")".repeat(too_deep));
+        assert_eq!(eval_z(&parens), Err(ParseError::Nesting));
+        assert_eq!(eval_z(&format!("{}1", "-".repeat(too_deep))), Err(ParseError::Nesting));
+        assert_eq!(eval_z(&vec!["2"; too_deep].join("^")), Err(ParseError::Nesting));
+
+        // chains do not nest.
+        assert_eq!(eval_z(&vec!["1"; too_deep].join(" + ")), Ok(Integer::new(too_deep as int)));
+        assert_eq!(eval_z(&vec!["1"; too_deep].join(" * ")), Ok(Integer::one()));
+    }
+
+    #[test]
+    fn size_of_powers() {
+        let two_power = eval_z("2^100000").unwrap();
+        assert_eq!(two_power.number.bits(), 100001);
+
+        assert_eq!(eval_z("((10^10000)^10000)^10000"), Err(ParseError::Size));
+        assert_eq!(eval_z("2^(10^30)"), Err(ParseError::Size));
+        assert_eq!(evaluate(&Rationals, "(1/3)^(10^6)", &Scope::new()), Err(ParseError::Size));
+
+        // powers of units do not grow.
+        assert_eq!(eval_z("(-1)^(10^30 + 1)"), Ok(Integer::new(-1)));
+        assert_eq!(evaluate(&GaussIntegers, "i^(10^30 + 3)", &Scope::new()), Ok(GaussInteger::new(0, -1)));
+    }
+
+    #[test]
+    fn degree_of_powers() {
+        let ev = RationalPolynomials;
+        assert_eq!(evaluate(&ev, "x^100000", &Scope::new()), Err(ParseError::Degree));
+        assert_eq!(evaluate(&ev, "(x + 1)^(10^30)", &Scope::new()), Err(ParseError::Degree));
+        assert_eq!(evaluate(&ev, "x^10001", &Scope::new()), Err(ParseError::Degree));
+        assert_eq!(evaluate(&ev, "(x^2 + 1)^50", &Scope::new()).map(|f| f.norm()), Ok(100));
+
+        // the coefficients grow too, and make for a quadratic multiplication.
+        assert_eq!(evaluate(&ev, "(x + 1)^10000", &Scope::new()), Err(ParseError::PolSize));
+        assert_eq!(evaluate(&ev, "(x + 1)^200", &Scope::new()).map(|f| f.norm()), Ok(200));
+        assert_eq!(evaluate(&ev, "(x/2 - 1)^100", &Scope::new()).map(|f| f.norm()), Ok(100));
+    }
+}
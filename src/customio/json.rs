@@ -0,0 +1,142 @@
+use super::*;
+
+// a minimal JSON tree, enough to serialize results. Elements of the algebras are written
+// as strings, since big integers do not survive a trip through a double; Number is only
+// for small counts such as degrees and exponents.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(usize),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    // an object, from its fields in order.
+    pub fn object(fields: Vec<(&str, Json)>) -> Json {
+        Json::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    pub fn render(&self) -> String {
+        match self {
+            Json::Null => "null".to_string(),
+            Json::Bool(b) => b.to_string(),
+            Json::Number(n) => n.to_string(),
+            Json::Str(s) => escape(s),
+            Json::Array(items) => {
+                let items: Vec<String> = items.iter().map(|item| item.render()).collect();
+                format!("[{}]", items.join(", "))
+            }
+            Json::Object(fields) => {
+                let fields: Vec<String> = fields.iter()
+                    .map(|(key, value)| format!("{}: {}", escape(key), value.render()))
+                    .collect();
+                format!("{{{}}}", fields.join(", "))
+            }
+        }
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut res = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+// the structure of a value, next to its text. Every value is an object with a "text"
+// field, as in SimpleDisplay; polynomials add their coefficients, lowest degree first.
+pub trait JsonDisplay {
+    fn json_display(&self) -> Json;
+}
+
+impl JsonDisplay for Integer {
+    fn json_display(&self) -> Json {
+        Json::object(vec![("text", Json::Str(self.simple_display()))])
+    }
+}
+
+impl JsonDisplay for Rational {
+    fn json_display(&self) -> Json {
+        let reduced = self.reduced();
+        Json::object(vec![
+            ("text", Json::Str(self.simple_display())),
+            ("numerator", Json::Str(reduced.numerator.to_string())),
+            ("denominator", Json::Str(reduced.denominator.to_string())),
+        ])
+    }
+}
+
+impl JsonDisplay for ComplexRational {
+    fn json_display(&self) -> Json {
+        Json::object(vec![
+            ("text", Json::Str(self.simple_display())),
+            ("real", Json::Str(self.real.simple_display())),
+            ("imag", Json::Str(self.imag.simple_display())),
+        ])
+    }
+}
+
+impl JsonDisplay for GaussInteger {
+    fn json_display(&self) -> Json {
+        Json::object(vec![
+            ("text", Json::Str(self.simple_display())),
+            ("real", Json::Str(self.real.simple_display())),
+            ("imag", Json::Str(self.imag.simple_display())),
+        ])
+    }
+}
+
+impl JsonDisplay for QPol {
+    fn json_display(&self) -> Json {
+        let reduced = self.reduced();
+        Json::object(vec![
+            ("text", Json::Str(self.simple_display())),
+            ("degree", degree_json(reduced.norm(), reduced.vector.iter().all(|c| c.is_zero()))),
+            ("coefficients", Json::Array(reduced.vector.iter().map(|c| Json::Str(c.simple_display())).collect())),
+        ])
+    }
+}
+
+impl JsonDisplay for ZPol {
+    fn json_display(&self) -> Json {
+        let reduced = self.reduced();
+        Json::object(vec![
+            ("text", Json::Str(self.simple_display())),
+            ("degree", degree_json(reduced.norm(), reduced.vector.iter().all(|c| c.is_zero()))),
+            ("coefficients", Json::Array(reduced.vector.iter().map(|c| Json::Str(c.simple_display())).collect())),
+        ])
+    }
+}
+
+// the zero polynomial has no degree.
+fn degree_json(norm: usize, is_zero: bool) -> Json {
+    if is_zero { Json::Null } else { Json::Number(norm) }
+}
+
+impl<T: SimpleDisplay + JsonDisplay> JsonDisplay for Factorization<T> {
+    fn json_display(&self) -> Json {
+        let factors = self.factors.iter()
+            .map(|(factor, expon)| Json::object(vec![
+                ("factor", factor.json_display()),
+                ("exponent", Json::Number(*expon)),
+            ]))
+            .collect();
+
+        Json::object(vec![
+            ("text", Json::Str(self.simple_display())),
+            ("unit", self.unit.json_display()),
+            ("factors", Json::Array(factors)),
+        ])
+    }
+}
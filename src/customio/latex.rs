@@ -0,0 +1,142 @@
+use super::*;
+use num_traits::{Signed, One, Zero};
+
+// LaTeX for lecture notes, to be pasted in math mode. Same principles as SimpleDisplay:
+// reduced, no surrounding whitespace, and parentheses only where they are needed.
+pub trait LatexDisplay {
+    fn latex_display(&self) -> String;
+}
+
+impl LatexDisplay for Integer {
+    fn latex_display(&self) -> String {
+        format!("{}", self.number)
+    }
+}
+
+// eg. "\frac{1}{2}", "-\frac{3}{4}", "5".
+impl LatexDisplay for Rational {
+    fn latex_display(&self) -> String {
+        let reduced = self.reduced();
+        if reduced.numerator.is_negative() {
+            format!("-{}", abs_latex(&reduced))
+        } else {
+            abs_latex(&reduced)
+        }
+    }
+}
+
+// eg. "\frac{1}{2}x^{10} - x + 3", highest degree first.
+impl LatexDisplay for Polynomial<Rational> {
+    fn latex_display(&self) -> String {
+        let reduced = self.reduced();
+        let mut terms: Vec<(Rational, String)> = Vec::new();
+
+        let mut i = reduced.vector.len();
+        while i > 0 {
+            i -= 1;
+            let monomial = match i {
+                0 => String::new(),
+                1 => "x".to_string(),
+                _ => format!("x^{{{i}}}"),
+            };
+            terms.push((reduced.vector[i].clone(), monomial));
+        }
+
+        join_terms(&terms)
+    }
+}
+
+impl LatexDisplay for ZPol {
+    fn latex_display(&self) -> String {
+        self.to_qpol().latex_display()
+    }
+}
+
+// eg. "3 + 4i", "-\frac{1}{2}i".
+impl LatexDisplay for ComplexRational {
+    fn latex_display(&self) -> String {
+        join_terms(&[(self.real.clone(), String::new()), (self.imag.clone(), "i".to_string())])
+    }
+}
+
+impl LatexDisplay for GaussInteger {
+    fn latex_display(&self) -> String {
+        self.to_complex_rational().latex_display()
+    }
+}
+
+// eg. "3 \cdot \left(x + 1\right)^{2} \cdot x^{3}", the unit dropped as in SimpleDisplay.
+impl<T: LatexDisplay> LatexDisplay for Factorization<T> {
+    fn latex_display(&self) -> String {
+        let mut terms: Vec<String> = Vec::new();
+
+        let unit = self.unit.latex_display();
+        let is_minus_one = unit == "-1";
+        if self.factors.is_empty() || unit != "1" && !is_minus_one {
+            terms.push(unit);
+        }
+
+        for (factor, expon) in &self.factors {
+            let str = factor.latex_display();
+            let is_single_term = !str.chars().skip(1).any(|c| c == '+' || c == '-');
+            let base = if is_single_term || *expon == 1 && self.factors.len() == 1 && terms.is_empty() {
+                str
+            } else {
+                format!("\\left({str}\\right)")
+            };
+
+            if *expon == 1 {
+                terms.push(base);
+            } else {
+                terms.push(format!("{base}^{{{expon}}}"));
+            }
+        }
+
+        let product = terms.join(" \\cdot ");
+        if is_minus_one && !self.factors.is_empty() {
+            format!("-{product}")
+        } else {
+            product
+        }
+    }
+}
+
+// |x|, for a reduced x.
+fn abs_latex(x: &Rational) -> String {
+    let num = x.numerator.abs();
+    if x.denominator.is_one() {
+        format!("{num}")
+    } else {
+        format!("\\frac{{{num}}}{{{}}}", x.denominator)
+    }
+}
+
+// coefficient-monomial pairs, eg. (-1, "x"), written as a sum. Zero terms are dropped,
+// as are coefficients of 1 before a monomial; the sum of no terms is "0".
+fn join_terms(terms: &[(Rational, String)]) -> String {
+    let mut output = String::new();
+
+    for (coeff, monomial) in terms {
+        let reduced = coeff.reduced();
+        if reduced.numerator.is_zero() {
+            continue;
+        }
+
+        let sign = match (reduced.numerator.is_negative(), output.is_empty()) {
+            (true, true) => "-",
+            (true, false) => " - ",
+            (false, true) => "",
+            (false, false) => " + ",
+        };
+        let is_unit = reduced.numerator.abs().is_one() && reduced.denominator.is_one();
+        let abs = if is_unit && !monomial.is_empty() { String::new() } else { abs_latex(&reduced) };
+
+        output = format!("{output}{sign}{abs}{monomial}");
+    }
+
+    if output.is_empty() {
+        "0".to_string()
+    } else {
+        output
+    }
+}
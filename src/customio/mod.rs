@@ -2,7 +2,19 @@ mod display;
 pub use display::SimpleDisplay;
 
 mod parse;
-pub use parse::{Parse, ParseError};
+pub use parse::{Parse, ParseError, MAX_DEGREE, MAX_BITS, MAX_POL_BITS, MAX_NESTING};
+
+mod expr;
+pub use expr::{Evaluator, Scope, evaluate};
+
+mod modular;
+pub use modular::PolMod;
+
+mod json;
+pub use json::{Json, JsonDisplay};
+
+mod latex;
+pub use latex::LatexDisplay;
 
 use super::arithmetic::*;
 use crate::{Duo, Trio};
\ No newline at end of file
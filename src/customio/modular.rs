@@ -0,0 +1,224 @@
+use super::*;
+use super::expr::check_degree;
+
+// evaluators for the algebras with a parameter: F(p), Zmod(n), their polynomials, and GF(p^n).
+// Values are always reduced.
+
+// polynomials over the ring described by M, ie. FPol(p) for Prime, Zmod(n)Pol for Modulus.
+pub struct PolMod<'a, M>(pub &'a M);
+
+impl Evaluator for Prime {
+    type Value = Integer;
+
+    fn constant(&self, n: &BigInt) -> Integer {
+        self.modulo(&Integer::from_bigint(n.clone()))
+    }
+
+    fn variable(&self, _name: &str) -> Option<Integer> {
+        None
+    }
+
+    fn add(&self, x: &Integer, y: &Integer) -> Integer {
+        Prime::add(self, x, y)
+    }
+
+    fn subtract(&self, x: &Integer, y: &Integer) -> Integer {
+        Prime::subtract(self, x, y)
+    }
+
+    fn multiply(&self, x: &Integer, y: &Integer) -> Integer {
+        Prime::multiply(self, x, y)
+    }
+
+    fn neg(&self, x: &Integer) -> Integer {
+        Prime::neg(self, x)
+    }
+
+    fn divide(&self, x: &Integer, y: &Integer) -> Result<Integer, ParseError> {
+        let Some(inv_y) = self.try_inv(y) else {
+            return Err(ParseError::ZeroDenom);
+        };
+
+        Ok(Prime::multiply(self, x, &inv_y))
+    }
+}
+
+impl Evaluator for Modulus {
+    type Value = Integer;
+
+    fn constant(&self, n: &BigInt) -> Integer {
+        self.modulo(&Integer::from_bigint(n.clone()))
+    }
+
+    fn variable(&self, _name: &str) -> Option<Integer> {
+        None
+    }
+
+    fn add(&self, x: &Integer, y: &Integer) -> Integer {
+        Modulus::add(self, x, y)
+    }
+
+    fn subtract(&self, x: &Integer, y: &Integer) -> Integer {
+        Modulus::subtract(self, x, y)
+    }
+
+    fn multiply(&self, x: &Integer, y: &Integer) -> Integer {
+        Modulus::multiply(self, x, y)
+    }
+
+    fn neg(&self, x: &Integer) -> Integer {
+        Modulus::neg(self, x)
+    }
+
+    fn divide(&self, x: &Integer, y: &Integer) -> Result<Integer, ParseError> {
+        if self.modulo(y).is_zero() {
+            return Err(ParseError::ZeroDenom);
+        }
+
+        Modulus::divide(self, x, y).map_err(|_| ParseError::NotInvertible)
+    }
+}
+
+impl Evaluator for PolMod<'_, Prime> {
+    type Value = ZPol;
+
+    fn constant(&self, n: &BigInt) -> ZPol {
+        self.0.modpol(&ZPol::as_polynomial(&Integer::from_bigint(n.clone())))
+    }
+
+    fn variable(&self, name: &str) -> Option<ZPol> {
+        (name == "x").then(|| ZPol::monomial(&Integer::one(), 1))
+    }
+
+    fn add(&self, x: &ZPol, y: &ZPol) -> ZPol {
+        self.0.addpol(x, y)
+    }
+
+    fn subtract(&self, x: &ZPol, y: &ZPol) -> ZPol {
+        self.0.subpol(x, y)
+    }
+
+    fn multiply(&self, x: &ZPol, y: &ZPol) -> ZPol {
+        self.0.mulpol(x, y)
+    }
+
+    fn neg(&self, x: &ZPol) -> ZPol {
+        self.0.negpol(x)
+    }
+
+    fn divide(&self, x: &ZPol, y: &ZPol) -> Result<ZPol, ParseError> {
+        let duo = self.0.try_divmodpol(x, y).ok_or(ParseError::ZeroDenom)?;
+        if duo.second != ZPol::zero() {
+            return Err(ParseError::Inexact);
+        }
+
+        Ok(duo.first)
+    }
+
+    fn remainder(&self, x: &ZPol, y: &ZPol) -> Result<ZPol, ParseError> {
+        Ok(self.0.try_divmodpol(x, y).ok_or(ParseError::ZeroDenom)?.second)
+    }
+
+    fn check(&self, x: &ZPol) -> Result<(), ParseError> {
+        check_degree(x.norm())
+    }
+
+    fn degree(&self, x: &ZPol) -> usize {
+        x.norm()
+    }
+}
+
+impl Evaluator for PolMod<'_, Modulus> {
+    type Value = ZPol;
+
+    fn constant(&self, n: &BigInt) -> ZPol {
+        self.0.modpol(&ZPol::as_polynomial(&Integer::from_bigint(n.clone())))
+    }
+
+    fn variable(&self, name: &str) -> Option<ZPol> {
+        (name == "x").then(|| ZPol::monomial(&Integer::one(), 1))
+    }
+
+    fn add(&self, x: &ZPol, y: &ZPol) -> ZPol {
+        self.0.addpol(x, y)
+    }
+
+    fn subtract(&self, x: &ZPol, y: &ZPol) -> ZPol {
+        self.0.subpol(x, y)
+    }
+
+    fn multiply(&self, x: &ZPol, y: &ZPol) -> ZPol {
+        self.0.mulpol(x, y)
+    }
+
+    fn neg(&self, x: &ZPol) -> ZPol {
+        self.0.negpol(x)
+    }
+
+    fn divide(&self, x: &ZPol, y: &ZPol) -> Result<ZPol, ParseError> {
+        if *y == ZPol::zero() {
+            return Err(ParseError::ZeroDenom);
+        }
+
+        let Ok(duo) = self.0.divmodpol(x, y) else {
+            return Err(ParseError::NotInvertible);
+        };
+        if duo.second != ZPol::zero() {
+            return Err(ParseError::Inexact);
+        }
+
+        Ok(duo.first)
+    }
+
+    fn remainder(&self, x: &ZPol, y: &ZPol) -> Result<ZPol, ParseError> {
+        if *y == ZPol::zero() {
+            return Err(ParseError::ZeroDenom);
+        }
+
+        let Ok(duo) = self.0.divmodpol(x, y) else {
+            return Err(ParseError::NotInvertible);
+        };
+
+        Ok(duo.second)
+    }
+
+    fn check(&self, x: &ZPol) -> Result<(), ParseError> {
+        check_degree(x.norm())
+    }
+
+    fn degree(&self, x: &ZPol) -> usize {
+        x.norm()
+    }
+}
+
+impl Evaluator for GaloisField {
+    type Value = ZPol;
+
+    fn constant(&self, n: &BigInt) -> ZPol {
+        self.reduce(&ZPol::as_polynomial(&Integer::from_bigint(n.clone())))
+    }
+
+    fn variable(&self, name: &str) -> Option<ZPol> {
+        (name == "x").then(|| self.reduce(&ZPol::monomial(&Integer::one(), 1)))
+    }
+
+    fn add(&self, x: &ZPol, y: &ZPol) -> ZPol {
+        GaloisField::add(self, x, y)
+    }
+
+    fn subtract(&self, x: &ZPol, y: &ZPol) -> ZPol {
+        GaloisField::subtract(self, x, y)
+    }
+
+    fn multiply(&self, x: &ZPol, y: &ZPol) -> ZPol {
+        GaloisField::multiply(self, x, y)
+    }
+
+    fn neg(&self, x: &ZPol) -> ZPol {
+        GaloisField::neg(self, x)
+    }
+
+    fn divide(&self, x: &ZPol, y: &ZPol) -> Result<ZPol, ParseError> {
+        GaloisField::divide(self, x, y).ok_or(ParseError::ZeroDenom)
+    }
+}
@@ -1,228 +1,95 @@
 use super::*;
-use regex::Regex;
+use super::expr::*;
 
 #[derive(PartialEq, Debug)]
 pub enum ParseError {
-    NotAscii, Format, Algebra, Op, Operand, NotPrime, ZeroDenom,
+    NotAscii, Format, Algebra, Op, Operand, NotPrime, ZeroDenom, Overflow, Degree, Size, PolSize, Nesting, Arity, Modulus, NotIrreducible, FieldDegree,
+    Inexact, NotInvertible, Remainder, Unknown(String), Name(String), Exponent(String), Braces(String), Query, OutputFormat(String),
 
     //only for debug use
     Debug, Debug1, Debug2, Debug3, Debug4
 }
 impl ParseError {
     pub fn print(&self){
-        let err_str: String = match *self {
-            ParseError::Algebra => "Failed to parse :ALG. Choose from: Z, Q, QPol, FPol(p), F(p), Zi.".to_string(),
+        println!("Error: {}\n", self.message());
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ParseError::Algebra => "Failed to parse :ALG. Choose from: Z, Q, QPol, FPol(p), F(p), Zi, Zmod(n), Zmod(n)Pol, GF(p^n).".to_string(),
             ParseError::ZeroDenom => "Zero cannot be a denominator.".to_string(),
-            ParseError::Op => "Failed to parse :Op. Choose from: Add, Sub, Mul, Div, Bezout, Divmod.".to_string(),
+            ParseError::Op => "Failed to parse :Op. Choose from: Add, Sub, Mul, Div, Mod, Bezout, Trace, Gcd, Lcm, Divmod, IsUnit, IsZeroDivisor, Inv, Factor, IsPrime, NextPrime, Totient, Sigma, NumDivisors, Mobius, Carmichael, Divisors, Crt, Solve, Congruence, Contfrac, Pell, Pow, Order, PrimRoot, IsPrimRoot, Dlog, Sqrt, Legendre, Jacobi.".to_string(),
             ParseError::Operand => "Failed to parse :Operand. Please check the documentation for format rules.".to_string(),
             ParseError::NotPrime => "It is not a prime number.".to_string(),
-            ParseError::Format => "Input failed to obey CLI syntax: [:Alg] :OP {:OPERAND1} {:OPERAND2}".to_string(),
+            ParseError::Overflow => "Number too large: it does not fit into a machine integer.".to_string(),
+            ParseError::Degree => format!("Polynomial degree too large. The limit is {MAX_DEGREE}."),
+            ParseError::Size => format!("Number too large: a power would take more than {MAX_BITS} bits."),
+            ParseError::PolSize => format!("Polynomial too large: the coefficients of a power would take more than {MAX_POL_BITS} bits together."),
+            ParseError::Nesting => format!("Expression nested too deeply. The limit is {MAX_NESTING} levels."),
+            ParseError::Arity => "Wrong number of operands for :Op. Unary operations take {:OPERAND}, Crt takes pairs {:RESIDUE} {:MODULUS}, Solve {:A} {:B} {:C} and Congruence {:A} {:B} {:MODULUS}, Gcd, Lcm and Bezout two or more operands, PrimRoot none, Sqrt, Legendre and Jacobi {:A} in F(p) but {:A} {:MODULUS} in Z, the others {:OPERAND1} {:OPERAND2}.".to_string(),
+            ParseError::Modulus => "The modulus must be at least 2.".to_string(),
+            ParseError::NotIrreducible => "The modulus of GF(p^n) must be an irreducible polynomial of degree n over F(p).".to_string(),
+            ParseError::FieldDegree => "The degree n of GF(p^n) must be at least 1.".to_string(),
+            ParseError::Inexact => "The division is not exact. Use % for the remainder.".to_string(),
+            ParseError::NotInvertible => "The divisor is not invertible in this algebra.".to_string(),
+            ParseError::Remainder => "% is only available in euclidean rings, eg. Z, Zi and polynomials.".to_string(),
+            ParseError::Unknown(name) => format!("Unknown name {name}."),
+            ParseError::Exponent(name) => format!("{name} cannot be an exponent. Exponents are integers in every algebra, and {name} is bound to something else."),
+            ParseError::Braces(op) => format!("{op} is an operation, not a name. Write its operands in braces: [:Alg] {op} {{:OPERAND1}} {{:OPERAND2}}."),
+            ParseError::Name(name) => format!("{name} cannot be a variable name. Use letters, digits and _, starting with a letter, except for x, i, ans, the commands and the operations."),
+            ParseError::Query => "The answer to a query is true or false, and divisors and contfrac give lists; none can be bound to a name.".to_string(),
+            ParseError::OutputFormat(name) => format!("Unknown format {name}. Choose from: pretty, plain, latex, json."),
+            ParseError::Format => "Input failed to obey CLI syntax: [:Alg] :OP {:OPERAND1} {:OPERAND2}, or [:Alg] :EXPRESSION, optionally preceded by let :NAME =. Also: vars, clear [:NAME], :format [:FORMAT [:STATEMENT]]".to_string(),
             _ => format!("{self:?}"),
-        };
-
-        println!("Error: {err_str}\n");
+        }
     }
 }
 
+// a polynomial is a dense vector of coefficients, so an exponent like x^99999999999
+// would be allocated in full. Refuse that at the door.
+pub const MAX_DEGREE: usize = 10000;
+
+// likewise, the size of a power of a number, estimated before it is computed.
+// Enough for 2^100000, but not for (10^10000)^10000.
+pub const MAX_BITS: u64 = 1 << 20;
+
+// the size of a power of a polynomial over Q: its number of coefficients times their bits.
+// Multiplication is quadratic in both, so this is far less; (x + 1)^360 just fits.
+pub const MAX_POL_BITS: u64 = 1 << 18;
+
+// the depth of an expression, see expr.rs.
+pub const MAX_NESTING: usize = 256;
+
 pub trait Parse: Sized {
-    fn parse(input: &str) -> Result<Self, ParseError>;
+    // names in the input are looked up in scope.
+    fn parse_in(input: &str, scope: &Scope) -> Result<Self, ParseError>;
+
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Self::parse_in(input, &Scope::new())
+    }
 }
 
 impl Parse for Rational {
-    fn parse(raw_input: &str) -> Result<Self, ParseError> {
-        let input_string: String = raw_input.split_ascii_whitespace().collect();
-        let input = input_string.as_str();
- 
-        let re_int = Regex::new(r"^([0-9]+)$").unwrap();
-        let re_neg_int = Regex::new(r"^\-([0-9]+)$").unwrap();
-        let re_wrapped_neg_int = Regex::new(r"^\(\-([0-9]+)\)$").unwrap();
-        let re_q = Regex::new(r"^([0-9]+)/([0-9]+)$").unwrap();
-        let re_neg_q = Regex::new(r"^\-([0-9]+)/([0-9]+)$").unwrap();
-        let re_wrapped_q = Regex::new(r"^\(([0-9]+)/([0-9]+)\)$").unwrap();
-        let re_neg_wrapped_q = Regex::new(r"^\-\(([0-9]+)/([0-9]+)\)$").unwrap();
-        let re_wrapped_neg_q = Regex::new(r"^\(\-([0-9]+)/([0-9]+)\)$").unwrap();
-
-        if re_int.is_match(input) {
-            let caps = re_int.captures(input).unwrap();
-            let (_, [int]) = caps.extract();
-            let output: int = int.parse().unwrap();
-            Ok(Rational::new(output, 1))
-        }
-        else if re_neg_int.is_match(input){
-            let caps = re_neg_int.captures(input).unwrap();
-            let (_, [int]) = caps.extract();
-            let output: int = int.parse().unwrap();
-            Ok(Rational::new(-output, 1))
-        }
-        else if re_wrapped_neg_int.is_match(input) {
-            let caps = re_wrapped_neg_int.captures(input).unwrap();
-            let (_, [int]) = caps.extract();
-            let output: int = int.parse().unwrap();
-            Ok(Rational::new(-output, 1))
-        }
-        else if re_q.is_match(input) {
-            let caps = re_q.captures(input).unwrap();
-            let (_, [num_str, denom_str]) = caps.extract();
-            let num: int = num_str.parse().unwrap();
-            let denom: int = denom_str.parse().unwrap();
-            if denom == 0 {
-                return Err(ParseError::ZeroDenom);
-            }
-            Ok(Rational::new(num, denom))
-        }
-        else if re_neg_q.is_match(input) {
-            let caps = re_neg_q.captures(input).unwrap();
-            let (_, [num_str, denom_str]) = caps.extract();
-            let num: int = num_str.parse().unwrap();
-            let denom: int = denom_str.parse().unwrap();
-            if denom == 0 {
-                return Err(ParseError::ZeroDenom);
-            }
-            Ok(Rational::new(-num, denom))
-        }
-        else if re_wrapped_q.is_match(input) {
-            let caps = re_wrapped_q.captures(input).unwrap();
-            let (_, [num_str, denom_str]) = caps.extract();
-            let num: int = num_str.parse().unwrap();
-            let denom: int = denom_str.parse().unwrap();
-            if denom == 0 {
-                return Err(ParseError::ZeroDenom);
-            }
-            Ok(Rational::new(num, denom))
-        }
-        else if re_neg_wrapped_q.is_match(input) {
-            let caps = re_neg_wrapped_q.captures(input).unwrap();
-            let (_, [num_str, denom_str]) = caps.extract();
-            let num: int = num_str.parse().unwrap();
-            let denom: int = denom_str.parse().unwrap();
-            if denom == 0 {
-                return Err(ParseError::ZeroDenom);
-            }
-            Ok(Rational::new(-num, denom))
-        }
-        else if re_wrapped_neg_q.is_match(input) {
-            let caps = re_wrapped_neg_q.captures(input).unwrap();
-            let (_, [num_str, denom_str]) = caps.extract();
-            let num: int = num_str.parse().unwrap();
-            let denom: int = denom_str.parse().unwrap();
-            if denom == 0 {
-                return Err(ParseError::ZeroDenom);
-            }
-            Ok(Rational::new(-num, denom))
-        }
-        else {
-            Err(ParseError::Operand)
-        }
+    fn parse_in(input: &str, scope: &Scope) -> Result<Self, ParseError> {
+        evaluate(&Rationals, input, scope)
     }
 }
 
 impl Parse for Integer {
-    fn parse(input: &str) -> Result<Self, ParseError> {
-        let re_int = Regex::new(r"^ *([0-9]+) *$").unwrap();
-        let re_neg_int = Regex::new(r"^ *\- *([0-9]+) *$").unwrap();
-        let re_wrapped_neg_int = Regex::new(r"^ *\( *\- *([0-9]+) *\) *$").unwrap();
-
-        if re_int.is_match(input) {
-            let caps = re_int.captures(input).unwrap();
-            let (_, [int]) = caps.extract();
-            let output: int = int.parse().unwrap();
-            Ok(Integer::new(output))
-        }
-        else if re_neg_int.is_match(input){
-            let caps = re_neg_int.captures(input).unwrap();
-            let (_, [int]) = caps.extract();
-            let output: int = int.parse().unwrap();
-            Ok(Integer::new(-output))
-        }
-        else if re_wrapped_neg_int.is_match(input) {
-            let caps = re_wrapped_neg_int.captures(input).unwrap();
-            let (_, [int]) = caps.extract();
-            let output: int = int.parse().unwrap();
-            Ok(Integer::new(-output))
-        }
-        else {
-            Err(ParseError::Operand)
-        }
+    fn parse_in(input: &str, scope: &Scope) -> Result<Self, ParseError> {
+        evaluate(&Integers, input, scope)
     }
 }
 
 impl Parse for Polynomial<Rational> {
-    fn parse(raw_input: &str) -> Result<Self, ParseError> {
-        let re_mon = Regex::new(r"^(.*)x\^?([0-9]*)$").unwrap();
-        // drop all whitespace
-        let input: String = raw_input.split_ascii_whitespace().collect();
-
-        // replace "+" with "+-" in order to split the polynomial into monomials
-        let string_ = input.replace("-", "+-");
-        
-        let str = string_.as_str();
-
-        let vec_str: Vec<&str> = str.split('+').collect();
-
-        let mut vec: Vec<Self> = Vec::new();
-
-        // monomial parsing. if empty then skip; if it contains "x" then use regex;
-        // if it has no "x" then treat as constant.
-        for str_mon in vec_str {
-            if str_mon.is_empty() {
-                // do nothing
-            }
-
-            else if re_mon.is_match(str_mon) {
-                let caps = re_mon.captures(str_mon).unwrap();
-                let (_, [str_coeff, str_expon]) = caps.extract();
-                let coeff: Rational;
-
-                // pay attention to special cases as in "-x" and "x", 
-                // where str_coeff cant be parsed normally
-                if str_coeff == "" {
-                    coeff = Rational::new(1,1);
-                } else if str_coeff == "-" {
-                    coeff = Rational::new(-1, 1)
-                } else {
-                    let res_coeff = Rational::parse(str_coeff);
-                    if res_coeff.is_err() {return Err(ParseError::Operand);}
-                    coeff = res_coeff.unwrap();
-                }
-                
-                // pay attention to "2x", as the exponential term is omitted 
-                let expon: usize = if str_expon.is_empty() {1} else {
-                    str_expon.parse::<usize>().unwrap()
-                };
-
-                let mon = Self::monomial(&coeff, expon);
-                
-                vec.push(mon);
-            }
-
-            else {
-                let res_con = Rational::parse(str_mon);
-                if res_con.is_err() { return Err(ParseError::Operand); }
-                
-                let con = res_con.unwrap();
-                let mon = Self::monomial(&con, 0);
-                
-                vec.push(mon);
-            }
-        } 
-
-        if vec.is_empty() {
-            return Err(ParseError::Operand);
-        }
-
-        let mut sum = Self::zero();
-
-        for p in vec {
-            sum = Self::add(&sum, &p);
-        }
-
-        Ok(sum)
+    fn parse_in(input: &str, scope: &Scope) -> Result<Self, ParseError> {
+        evaluate(&RationalPolynomials, input, scope)
     }
 }
 
 impl Parse for ZPol {
-    fn parse(input: &str) -> Result<Self, ParseError> {
-        let qpol = Polynomial::<Rational>::parse(input)?;
+    fn parse_in(input: &str, scope: &Scope) -> Result<Self, ParseError> {
+        let qpol = Polynomial::<Rational>::parse_in(input, scope)?;
         
         let Some(zpol) = qpol.to_zpol() else {
             return Err(ParseError::Operand);
@@ -232,42 +99,14 @@ impl Parse for ZPol {
     }
 }
 
-// trick: use Polynomial Parse for Complex parse
 impl Parse for ComplexRational {
-    fn parse(input: &str) -> Result<Self, ParseError> {
-        let pol_str = input.replace("i", "x");
-        let pol = QPol::parse(&pol_str)?;
-
-        if pol.norm() >= 2 {
-            return Err(ParseError::Operand);
-        }
-        else if pol.norm() == 1 {
-            let res = ComplexRational{
-                real: pol.coefficient(0),
-                imag: pol.coefficient(1)
-            };
-            
-            return Ok(res);
-        }
-        else {
-            let res = ComplexRational {
-                real: pol.coefficient(0),
-                imag: Rational::zero()
-            };
-
-            return Ok(res);
-        }
+    fn parse_in(input: &str, scope: &Scope) -> Result<Self, ParseError> {
+        evaluate(&ComplexRationals, input, scope)
     }
 }
 
 impl Parse for GaussInteger {
-    fn parse(input: &str) -> Result<Self, ParseError> {
-        let rat = ComplexRational::parse(input)?;
-
-        let Some(res) = rat.to_gauss_integer() else {
-            return Err(ParseError::Operand);
-        };
-
-        Ok(res)
+    fn parse_in(input: &str, scope: &Scope) -> Result<Self, ParseError> {
+        evaluate(&GaussIntegers, input, scope)
     }
-}
\ No newline at end of file
+}
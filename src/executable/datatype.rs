@@ -1,30 +1,184 @@
-use crate::arithmetic::int;
+use crate::arithmetic::{BigInt, GaloisField};
+use crate::customio::{Json, JsonDisplay, LatexDisplay, Scope, SimpleDisplay};
+use super::options::Format;
 
-#[derive(PartialEq, Copy, Clone, Debug)]
+// GF carries p, n, and optionally the modulus as typed; it is auto-chosen if absent.
+#[derive(PartialEq, Clone, Debug)]
 pub(super) enum Algebra {
-    Z, Q, QPol, F(int), FPol(int), Zi
+    Z, Q, QPol, F(BigInt), FPol(BigInt), Zi, Zmod(BigInt), ZmodPol(BigInt),
+    GF(BigInt, usize, Option<String>),
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub(super) enum Operation {
     Add, Sub, Mul, Div,
     Mod, DivMod, Bezout,
+    // bezout, with the steps of the extended Euclidean algorithm.
+    Trace,
+    Gcd, Lcm,
+    // the multiplicative group of F(p). pow takes an integer exponent;
+    // dlog {g}{h} is the x with g^x = h.
+    Pow, Order, PrimRoot, IsPrimRoot, Dlog,
+    // quadratic residues. In Z, the prime or odd modulus is a second operand.
+    Sqrt, Legendre, Jacobi,
+    IsUnit, IsZeroDivisor, Inv,
+    Factor, IsPrime, NextPrime,
+    // arithmetic functions of a positive integer. divisors lists them all.
+    Totient, Sigma, NumDivisors, Mobius, Carmichael, Divisors,
+    Crt,
+    // solve {a}{b}{c} for a X + b Y = c; congruence {a}{b}{n} for a X = b mod n, in Z.
+    Solve, Congruence,
+    // continued fractions, of a rational in Q or of sqrt(d) in Z, and x^2 - d y^2 = +-1 in Z.
+    Contfrac, Pell,
+    // a bare expression, eg. "[Q] (3/4 + 1/6) * 2". Its only operand is the expression.
+    Eval,
 }
 
+impl Operation {
+    // queries answer true or false, and divisors and contfrac lists; none can be bound to a name.
+    pub fn is_query(&self) -> bool {
+        matches!(self, Operation::IsUnit | Operation::IsZeroDivisor | Operation::IsPrime | Operation::IsPrimRoot
+            | Operation::Divisors | Operation::Contfrac)
+    }
+
+    // totient and its kin, defined on positive integers only.
+    pub fn is_arithmetic_function(&self) -> bool {
+        matches!(self, Operation::Totient | Operation::Sigma | Operation::NumDivisors
+            | Operation::Mobius | Operation::Carmichael | Operation::Divisors)
+    }
+
+    // whether the operation takes count operands, i.e. {...} blocks.
+    pub fn accepts(&self, count: usize) -> bool {
+        match self {
+            Operation::IsUnit | Operation::IsZeroDivisor | Operation::Inv
+            | Operation::Factor | Operation::IsPrime | Operation::NextPrime
+            | Operation::Order | Operation::IsPrimRoot
+            | Operation::Totient | Operation::Sigma | Operation::NumDivisors
+            | Operation::Mobius | Operation::Carmichael | Operation::Divisors
+            | Operation::Contfrac | Operation::Pell
+            | Operation::Eval => count == 1,
+            // typed without braces, eg. "[F(7)] primroot".
+            Operation::PrimRoot => count == 0,
+            // pairs {residue}{modulus}, at least one of them.
+            Operation::Crt => count >= 2 && count.is_multiple_of(2),
+            Operation::Bezout | Operation::Gcd | Operation::Lcm => count >= 2,
+            Operation::Solve | Operation::Congruence => count == 3,
+            Operation::Sqrt | Operation::Legendre | Operation::Jacobi => count == 1 || count == 2,
+            _ => count == 2,
+        }
+    }
+}
+
+// operands are kept in the order they are typed. raw_parse guarantees
+// that op.accepts() their number.
+// a line typed in the REPL.
+#[derive(Debug)]
+pub(super) enum Statement {
+    Command(RawCommand),
+    // let name = command. The main result of the command is bound to name.
+    Let(String, RawCommand),
+    Vars,
+    // clear, or clear name.
+    Clear(Option<String>),
+    // :format name sets the output format, :format alone shows it.
+    Format(Option<Format>),
+    // :format name statement, in that format for this statement only.
+    Formatted(Format, Box<Statement>),
+}
+
+// scope holds the variables of the session that live in alg, and field the cached field
+// of a GF(p^n) command; both are filled in by the session.
 #[derive(Debug)]
 pub(super) struct RawCommand {
     pub alg: Algebra,
     pub op: Operation,
-    pub operand1: String,
-    pub operand2: String,
+    pub operands: Vec<String>,
+    pub scope: Scope,
+    pub field: Option<GaloisField>,
 }
 
+// opers are the parsed operands, displayed; they match RawCommand::operands one to one.
+// subs are the other components after main, in an order fixed by the operation:
+// U_1, ..., U_n for bezout, R for divmod, M for crt, the unit of a factorization,
+// the inverse or witness of a query, if there is one, and for a prime found by isprime
+// whether its primality is proven.
+// context describes what the evaluation found out about the algebra itself,
+// eg. the modulus chosen for GF(p^n).
+// table holds the rows of a trace: dividend, quotient, divisor, remainder, s, t
+// for each division of the Euclidean algorithm. It is empty otherwise.
 #[derive(Debug)]
 pub(super) struct RawResult {
-    pub oper1: String,
-    pub oper2: String,
-    pub main: String,
-    pub sub1: Option<String>,
-    pub sub2: Option<String>,
+    pub opers: Vec<Value>,
+    pub main: Value,
+    pub subs: Vec<Value>,
+    pub context: Option<String>,
+    pub table: Vec<Vec<Value>>,
+}
+
+impl RawResult {
+    // most results have neither context nor table; the others set them on top of this.
+    pub fn new(opers: Vec<Value>, main: Value, subs: Vec<Value>) -> Self {
+        RawResult { opers, main, subs, context: None, table: Vec::new() }
+    }
+}
+
+// a value in a result: text is what the pretty and plain formats show, json its structure,
+// latex its rendering for the latex format.
+#[derive(Clone, Debug)]
+pub(super) struct Value {
+    pub text: String,
+    pub json: Json,
+    pub latex: String,
 }
 
+impl Value {
+    pub fn of<T: SimpleDisplay + JsonDisplay + LatexDisplay>(x: &T) -> Self {
+        Value { text: x.simple_display(), json: x.json_display(), latex: x.latex_display() }
+    }
+
+    // the answer of a query.
+    pub fn boolean(b: bool) -> Self {
+        Value { text: b.to_string(), json: Json::Bool(b), latex: format!("\\text{{{b}}}") }
+    }
+
+    // eg. "1, 2, 3, 6", as a set in latex.
+    pub fn list<T: SimpleDisplay + JsonDisplay + LatexDisplay>(xs: &[T]) -> Self {
+        let items: Vec<Value> = xs.iter().map(Value::of).collect();
+        let texts: Vec<String> = items.iter().map(|x| x.text.clone()).collect();
+        let latexes: Vec<String> = items.iter().map(|x| x.latex.clone()).collect();
+
+        Value {
+            text: texts.join(", "),
+            json: Json::Array(items.into_iter().map(|x| x.json).collect()),
+            latex: format!("\\{{{}\\}}", latexes.join(", ")),
+        }
+    }
+}
+
+
+// names as typed in the CLI, used in error messages.
+impl std::fmt::Display for Algebra {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Algebra::F(p) => write!(f, "F({p})"),
+            Algebra::FPol(p) => write!(f, "FPol({p})"),
+            Algebra::Zmod(n) => write!(f, "Zmod({n})"),
+            Algebra::ZmodPol(n) => write!(f, "Zmod({n})Pol"),
+            Algebra::GF(p, n, None) => write!(f, "GF({p}^{n})"),
+            Algebra::GF(p, n, Some(m)) => write!(f, "GF({p}^{n}, {m})"),
+            _ => write!(f, "{self:?}"),
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{self:?}").to_lowercase())
+    }
+}
@@ -1,56 +1,105 @@
 use super::datatype::*;
+use crate::arithmetic::BigInt;
+use crate::customio::Json;
 
 pub(super) fn display_ring (cmd: RawCommand, res: RawResult) -> String {
     let op  = cmd.op;
-    if op == Operation::Bezout {
-        let main_str = "U * F + V * G = H, where";
-        let u_str = format!("U = {}", res.sub1.unwrap());
-        let f_str = format!("F = {}", res.oper1);
-        let v_str = format!("V = {}", res.sub2.unwrap());
-        let g_str = format!("G = {}", res.oper2);
-        let h_str = format!("[GCD] H = {}", res.main);
+    if op == Operation::Factor {
+        let main_str = format!("F = {}, where", res.main);
+        let f_str = format!("F = {}", res.opers[0]);
 
-        return format!("{main_str}\n{u_str},\n{f_str},\n{v_str},\n{g_str},\n{h_str}.");
+        return format!("{main_str}\n{f_str}.");
+    }
+    else if op == Operation::IsPrime {
+        let x = &res.opers[0];
+        // a prime beyond MAX_PROVEN_BITS passed Baillie–PSW only.
+        return match (res.main.text.as_str(), res.subs.first()) {
+            ("true", Some(proven)) if proven.text == "false" => format!("{x} is a probable prime."),
+            ("true", _) => format!("{x} is prime."),
+            _ => format!("{x} is not prime."),
+        };
+    }
+    else if op == Operation::NextPrime {
+        return format!("The next prime after {} is {}.", res.opers[0], res.main);
+    }
+    else if op == Operation::Divisors {
+        return format!("The divisors of {} are {}.", res.opers[0], res.main);
+    }
+    else if op.is_arithmetic_function() {
+        return format!("{op}({}) = {}.", res.opers[0], res.main);
+    }
+
+    if op == Operation::Crt {
+        return display_crt("", &res);
+    }
+    if op == Operation::Solve {
+        return display_solve("", &res);
+    }
+    if op == Operation::Congruence {
+        return display_congruence(&res);
+    }
+    if op == Operation::Contfrac {
+        return format!("sqrt({}) = {}, with period {}.", res.opers[0], res.main, res.subs[0]);
+    }
+    if op == Operation::Pell {
+        return display_pell(&res);
+    }
+
+    if matches!(op, Operation::Sqrt | Operation::Legendre | Operation::Jacobi) {
+        return display_residue(op, &res.opers[1].text, &res);
+    }
+
+    if op == Operation::Bezout || op == Operation::Trace {
+        display_bezout("", &res)
+    }
+    else if op == Operation::Gcd || op == Operation::Lcm {
+        display_gcd(op, "", &res)
     }
     else if op == Operation::DivMod {
         let main_str = "F / G = Q ... R, where";
-        let f_str = format!("F = {}", res.oper1);
-        let g_str = format!("G = {}", res.oper2);
+        let f_str = format!("F = {}", res.opers[0]);
+        let g_str = format!("G = {}", res.opers[1]);
         let q_str = format!("Q = {}", res.main);
-        let r_str = format!("R = {}", res.sub1.unwrap());
+        let r_str = format!("R = {}", res.subs[0]);
 
-        return format!("{main_str}\n{f_str},\n{g_str},\n{q_str},\n{r_str}.");
+        format!("{main_str}\n{f_str},\n{g_str},\n{q_str},\n{r_str}.")
     }
     else {
-        return format!("{}", res.main);
+        res.main.to_string()
     }
 }
 
-pub(super) fn display_fpol(cmd: RawCommand, res: RawResult) -> String{
+pub(super) fn display_fpol(p: &BigInt, cmd: RawCommand, res: RawResult) -> String{
     let op  = cmd.op;
 
-    let Algebra::FPol(p) = cmd.alg else{
-        panic!();
-    };
+    if op == Operation::Factor {
+        let main_str = format!("F = {} mod {p}, where", res.main);
+        let f_str = format!("F = {}", res.opers[0]);
 
-    if op == Operation::Bezout {
-        let main_str = format!("U * F + V * G = H mod {p}, where");
-        let u_str = format!("U = {}", res.sub1.unwrap());
-        let f_str = format!("F = {}", res.oper1);
-        let v_str = format!("V = {}", res.sub2.unwrap());
-        let g_str = format!("G = {}", res.oper2);
-        let h_str = format!("[GCD] H = {}", res.main);
+        return format!("{main_str}\n{f_str}.");
+    }
+
+    if op == Operation::Crt {
+        return display_crt(&format!(" over F({p})"), &res);
+    }
+    if op == Operation::Solve {
+        return display_solve(&format!(" mod {p}"), &res);
+    }
 
-        return format!("{main_str}\n{u_str},\n{f_str},\n{v_str},\n{g_str},\n{h_str}.");
+    if op == Operation::Bezout || op == Operation::Trace {
+        display_bezout(&format!(" mod {p}"), &res)
+    }
+    else if op == Operation::Gcd || op == Operation::Lcm {
+        display_gcd(op, &format!(" mod {p}"), &res)
     }
     else if op == Operation::DivMod {
         let main_str = format!("F / G = Q ... R mod {p}, where");
-        let f_str = format!("F = {}", res.oper1);
-        let g_str = format!("G = {}", res.oper2);
+        let f_str = format!("F = {}", res.opers[0]);
+        let g_str = format!("G = {}", res.opers[1]);
         let q_str = format!("Q = {}", res.main);
-        let r_str = format!("R = {}", res.sub1.unwrap());
+        let r_str = format!("R = {}", res.subs[0]);
 
-        return format!("{main_str}\n{f_str},\n{g_str},\n{q_str},\n{r_str}.");
+        format!("{main_str}\n{f_str},\n{g_str},\n{q_str},\n{r_str}.")
     }
     else {
         let operator = match op {
@@ -58,36 +107,483 @@ pub(super) fn display_fpol(cmd: RawCommand, res: RawResult) -> String{
             Operation::Sub => "-",
             Operation::Mul => "*",
             Operation::Mod => "%",
-            _ => panic!(),
+            _ => return res.main.text,
         };
 
         let main_str = format!("F {operator} G = H mod {p}, where");
-        let f_str = format!("F = {}", res.oper1);
-        let g_str = format!("G = {}", res.oper2);
+        let f_str = format!("F = {}", res.opers[0]);
+        let g_str = format!("G = {}", res.opers[1]);
         let h_str = format!("H = {}", res.main);
 
-        return format!("{main_str}\n{f_str},\n{g_str},\n{h_str}.")
+        format!("{main_str}\n{f_str},\n{g_str},\n{h_str}.")
     }    
 }
 
+// the names of the operands and coefficients of a bezout: F, G and U, V for two operands,
+// F_k and U_k for more.
+fn bezout_names(count: usize) -> Vec<(String, String)> {
+    if count == 2 {
+        return vec![("F".to_string(), "U".to_string()), ("G".to_string(), "V".to_string())];
+    }
+
+    (1..=count).map(|k| (format!("F_{k}"), format!("U_{k}"))).collect()
+}
+
+// "U * F + V * G = H, where" followed by the values, after the table of a trace.
+fn display_bezout(over: &str, res: &RawResult) -> String {
+    let names = bezout_names(res.opers.len());
+    let terms: Vec<String> = names.iter().map(|(f, u)| format!("{u} * {f}")).collect();
+
+    let mut lines = vec![format!("{} = H{over}, where", terms.join(" + "))];
+    for (k, (f, u)) in names.iter().enumerate() {
+        lines.push(format!("{u} = {},", res.subs[k]));
+        lines.push(format!("{f} = {},", res.opers[k]));
+    }
+    lines.push(format!("[GCD] H = {}.", res.main));
+
+    format!("{}{}", display_table(&res.table), lines.join("\n"))
+}
+
+// eg. "gcd(12, 18, 27) = 3."
+fn display_gcd(op: Operation, over: &str, res: &RawResult) -> String {
+    let operands: Vec<String> = res.opers.iter().map(|x| x.to_string()).collect();
+    format!("{op}({}) = {}{over}.", operands.join(", "), res.main)
+}
+
+// eg. "x^2 = 2 mod 7 for x = 3 and x = 4.", or "(2/7) = 1." for the symbols.
+fn display_residue(op: Operation, modulus: &str, res: &RawResult) -> String {
+    let a = &res.opers[0];
+    if op != Operation::Sqrt {
+        return format!("({a}/{modulus}) = {}.", res.main);
+    }
+
+    match res.subs.first() {
+        Some(other) => format!("x^2 = {a} mod {modulus} for x = {} and x = {other}.", res.main),
+        None => format!("x^2 = {a} mod {modulus} for x = {} only.", res.main),
+    }
+}
+
+// "X = x (mod M), which solves" followed by the congruences, one per line.
+fn display_crt(over: &str, res: &RawResult) -> String {
+    let main_str = format!("X = {} (mod {}){over}, which solves", res.main, res.subs[0]);
+    let congruences: Vec<String> = res.opers.chunks(2)
+        .map(|pair| format!("X = {} (mod {})", pair[0], pair[1]))
+        .collect();
+
+    format!("{main_str}\n{}.", congruences.join(",\n"))
+}
+
+// the solutions X = X0 + S T, Y = Y0 - R T of A X + B Y = C.
+fn display_solve(over: &str, res: &RawResult) -> String {
+    let main_str = format!("X = X0 + S T and Y = Y0 - R T, T arbitrary, solve A X + B Y = C{over}, where");
+    let lines = [
+        format!("A = {}", res.opers[0]),
+        format!("B = {}", res.opers[1]),
+        format!("C = {}", res.opers[2]),
+        format!("X0 = {}", res.main),
+        format!("Y0 = {}", res.subs[0]),
+        format!("S = {}", res.subs[1]),
+        format!("R = {}", res.subs[2]),
+    ];
+
+    format!("{main_str}\n{}.", lines.join(",\n"))
+}
+
+// "X = x (mod m), which solves" the congruence, then the solutions mod n if there are several.
+fn display_congruence(res: &RawResult) -> String {
+    let (a, b, n) = (&res.opers[0], &res.opers[1], &res.opers[2]);
+    let main_str = format!("X = {} (mod {}), which solves\n{a} X = {b} (mod {n}).", res.main, res.subs[0]);
+    if res.subs[1].text == "1" {
+        return main_str;
+    }
+
+    match res.subs.get(2) {
+        Some(solutions) => format!("{main_str}\nMod {n}, the solutions are {solutions}."),
+        None => format!("{main_str}\nMod {n}, there are {} solutions.", res.subs[1]),
+    }
+}
+
+// eg. "x^2 - 2 y^2 = -1 for x = 1, y = 1, and x^2 - 2 y^2 = 1 for x = 3, y = 2."
+fn display_pell(res: &RawResult) -> String {
+    let d = &res.opers[0];
+    let main_str = format!("x^2 - {d} y^2 = {} for x = {}, y = {}", res.subs[1], res.main, res.subs[0]);
+
+    match (res.subs.get(2), res.subs.get(3)) {
+        (Some(x), Some(y)) => format!("{main_str}, and x^2 - {d} y^2 = 1 for x = {x}, y = {y}."),
+        _ => format!("{main_str}."),
+    }
+}
+
+pub(super) fn display_q(cmd: RawCommand, res: RawResult) -> String {
+    if cmd.op == Operation::Contfrac {
+        return format!("{} = {}, with convergents {}.", res.opers[0], res.main, res.subs[0]);
+    }
+
+    display_field(cmd, res)
+}
+
 pub(super) fn display_field(_cmd: RawCommand, res: RawResult) -> String {
-    format!("{}", res.main)
+    res.main.to_string()
 }
 
-pub(super) fn display_f(cmd: RawCommand, res: RawResult) -> String {
+pub(super) fn display_f(p: &BigInt, cmd: RawCommand, res: RawResult) -> String {
     let op = cmd.op;
 
-    let Algebra::F(p) = cmd.alg else{
-        panic!();
+    match op {
+        Operation::Pow => {
+            let e = &res.opers[1].text;
+            let e = if e.starts_with('-') { format!("({e})") } else { e.clone() };
+            return format!("{}^{e} = {} mod {p}.", res.opers[0], res.main);
+        }
+        Operation::Order => return format!("The order of {} mod {p} is {}.", res.opers[0], res.main),
+        Operation::PrimRoot => return format!("The smallest primitive root mod {p} is {}.", res.main),
+        Operation::Dlog => {
+            return format!("{}^x = {} mod {p} for x = {}.", res.opers[0], res.opers[1], res.main);
+        }
+        Operation::Sqrt | Operation::Legendre | Operation::Jacobi => {
+            return display_residue(op, &p.to_string(), &res);
+        }
+        Operation::IsPrimRoot => {
+            let x = &res.opers[0];
+            return match res.main.text.as_str() {
+                "true" => format!("{x} is a primitive root mod {p}."),
+                _ => format!("{x} is not a primitive root mod {p}."),
+            };
+        }
+        _ => (),
+    }
+
+    let operator = match op {
+        Operation::Add => "+",
+        Operation::Sub => "-",
+        Operation::Mul => "*",
+        Operation::Div => "/",
+        _ => return res.main.text,
     };
 
+    format!("{} {} {} = {} mod {}.", res.opers[0], operator, res.opers[1], res.main, p)
+}
+
+pub(super) fn display_zmod(n: &BigInt, cmd: RawCommand, res: RawResult) -> String {
+    let op = cmd.op;
+    let x = &res.opers[0];
+
+    if op == Operation::IsUnit {
+        return match res.subs.first() {
+            Some(inv) => format!("{x} is a unit mod {n}, with inverse {inv}."),
+            None => format!("{x} is not a unit mod {n}."),
+        };
+    }
+    else if op == Operation::IsZeroDivisor {
+        return match res.subs.first() {
+            Some(y) => format!("{x} is a zero divisor mod {n}, since {x} * {y} = 0 mod {n}."),
+            None => format!("{x} is not a zero divisor mod {n}."),
+        };
+    }
+    else if op == Operation::Pow {
+        let e = &res.opers[1].text;
+        let e = if e.starts_with('-') { format!("({e})") } else { e.clone() };
+        return format!("{x}^{e} = {} mod {n}.", res.main);
+    }
+
     let operator = match op {
         Operation::Add => "+",
         Operation::Sub => "-",
         Operation::Mul => "*",
         Operation::Div => "/",
-        _ => panic!(),
+        _ => return res.main.text,
     };
 
-    format!("{} {} {} = {} mod {}.", res.oper1, operator, res.oper2, res.main, p)
-}
\ No newline at end of file
+    format!("{} {} {} = {} mod {}.", res.opers[0], operator, res.opers[1], res.main, n)
+}
+
+pub(super) fn display_gf(cmd: RawCommand, res: RawResult) -> String {
+    let op = cmd.op;
+
+    let Algebra::GF(p, n, _) = cmd.alg else {
+        return res.main.text;
+    };
+    let field = match res.context {
+        Some(modulus) => format!("GF({p}^{n}) = F({p})[x]/({modulus})"),
+        None => format!("GF({p}^{n})"),
+    };
+
+    if op == Operation::Inv {
+        let main_str = format!("F * H = 1 in {field}, where");
+        let f_str = format!("F = {}", res.opers[0]);
+        let h_str = format!("H = {}", res.main);
+
+        return format!("{main_str}\n{f_str},\n{h_str}.");
+    }
+
+    let operator = match op {
+        Operation::Add => "+",
+        Operation::Sub => "-",
+        Operation::Mul => "*",
+        Operation::Div => "/",
+        _ => return res.main.text,
+    };
+
+    let main_str = format!("F {operator} G = H in {field}, where");
+    let f_str = format!("F = {}", res.opers[0]);
+    let g_str = format!("G = {}", res.opers[1]);
+    let h_str = format!("H = {}", res.main);
+
+    format!("{main_str}\n{f_str},\n{g_str},\n{h_str}.")
+}
+
+const TABLE_HEADER: [&str; 6] = ["dividend", "quotient", "divisor", "remainder", "s", "t"];
+
+// the steps of a trace as a table with aligned columns, followed by a blank line.
+// Empty if there are no steps.
+fn display_table(table: &[Vec<Value>]) -> String {
+    if table.is_empty() {
+        return String::new();
+    }
+
+    let mut widths: Vec<usize> = TABLE_HEADER.iter().map(|head| head.len()).collect();
+    for row in table {
+        for (j, value) in row.iter().enumerate() {
+            widths[j] = widths[j].max(value.text.len());
+        }
+    }
+
+    let pad = |cells: Vec<&str>| -> String {
+        let padded: Vec<String> = cells.iter().zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        padded.join(" | ").trim_end().to_string()
+    };
+
+    let mut lines = vec![
+        "Euclidean algorithm, where dividend = quotient * divisor + remainder".to_string(),
+        "and remainder = s * F + t * G:".to_string(),
+        pad(TABLE_HEADER.to_vec()),
+    ];
+    for row in table {
+        lines.push(pad(row.iter().map(|value| value.text.as_str()).collect()));
+    }
+
+    format!("{}\n\n", lines.join("\n"))
+}
+
+// one JSON object per result, for tools. Components are named after the operation,
+// eg. gcd, u and v for bezout; the GF modulus, if any, is the context.
+pub(super) fn display_json(n: usize, name: Option<String>, cmd: RawCommand, res: RawResult) -> Json {
+    let main = Some(res.main.json);
+    let subs_json: Vec<Json> = res.subs.into_iter().map(|x| x.json).collect();
+    let sub1 = subs_json.first().cloned();
+    let sub2 = subs_json.get(1).cloned();
+    let components = match cmd.op {
+        // u and v only for two operands; the coefficients are listed for any number.
+        Operation::Bezout | Operation::Trace if res.opers.len() == 2 => vec![("gcd", main), ("u", sub1), ("v", sub2)],
+        Operation::Bezout => vec![("gcd", main)],
+        Operation::DivMod => vec![("quotient", main), ("remainder", sub1)],
+        Operation::Crt => vec![("residue", main), ("modulus", sub1)],
+        Operation::Solve => vec![("x", main), ("y", sub1), ("x_step", sub2), ("y_step", subs_json.get(2).cloned())],
+        Operation::Contfrac if cmd.alg == Algebra::Q => vec![("value", main), ("convergents", sub1)],
+        Operation::Contfrac => vec![("value", main), ("period_length", sub1)],
+        Operation::Pell => vec![("x", main), ("y", sub1), ("norm", sub2),
+            ("plus_x", subs_json.get(2).cloned()), ("plus_y", subs_json.get(3).cloned())],
+        Operation::Congruence => vec![("residue", main), ("modulus", sub1), ("count", sub2), ("solutions", subs_json.get(2).cloned())],
+        Operation::Factor => vec![("factorization", main)],
+        Operation::Sqrt => vec![("value", main), ("other_root", sub1)],
+        Operation::IsUnit => vec![("value", main), ("inverse", sub1)],
+        Operation::IsZeroDivisor => vec![("value", main), ("witness", sub1)],
+        Operation::IsPrime => vec![("value", main), ("proven", sub1)],
+        _ => vec![("value", main)],
+    };
+    let mut result: Vec<(&str, Json)> = components.into_iter()
+        .map(|(key, value)| (key, value.unwrap_or(Json::Null)))
+        .collect();
+
+    if cmd.op == Operation::Bezout || cmd.op == Operation::Trace {
+        result.push(("coefficients", Json::Array(subs_json)));
+    }
+    if cmd.op == Operation::Trace {
+        let steps = res.table.into_iter()
+            .map(|row| Json::object(TABLE_HEADER.into_iter().zip(row.into_iter().map(|x| x.json)).collect()))
+            .collect();
+        result.push(("steps", Json::Array(steps)));
+    }
+
+    Json::object(vec![
+        ("n", Json::Number(n)),
+        ("name", name.map_or(Json::Null, Json::Str)),
+        ("algebra", Json::Str(cmd.alg.to_string())),
+        ("operation", Json::Str(cmd.op.to_string())),
+        ("operands", Json::Array(res.opers.into_iter().map(|x| x.json).collect())),
+        ("result", Json::object(result)),
+        ("context", res.context.map_or(Json::Null, Json::Str)),
+    ])
+}
+
+// the algebra as it is written in math mode.
+fn algebra_latex(alg: &Algebra) -> String {
+    match alg {
+        Algebra::Z => "\\mathbb{Z}".to_string(),
+        Algebra::Q => "\\mathbb{Q}".to_string(),
+        Algebra::QPol => "\\mathbb{Q}[x]".to_string(),
+        Algebra::F(p) => format!("\\mathbb{{F}}_{{{p}}}"),
+        Algebra::FPol(p) => format!("\\mathbb{{F}}_{{{p}}}[x]"),
+        Algebra::Zi => "\\mathbb{Z}[i]".to_string(),
+        Algebra::Zmod(n) => format!("\\mathbb{{Z}}/{n}\\mathbb{{Z}}"),
+        Algebra::ZmodPol(n) => format!("(\\mathbb{{Z}}/{n}\\mathbb{{Z}})[x]"),
+        Algebra::GF(p, n, _) => format!("\\mathbb{{F}}_{{{p}^{{{n}}}}}"),
+    }
+}
+
+// a value as the first operand of + - or *: wrapped in \left( \right) unless it is a single term.
+fn operand_latex(value: &Value) -> String {
+    let is_single_term = !value.latex.chars().skip(1).any(|c| c == '+' || c == '-');
+    if is_single_term {
+        value.latex.clone()
+    } else {
+        format!("\\left({}\\right)", value.latex)
+    }
+}
+
+// a value after * / or mod, or as a base: negative single terms are wrapped too, eg. 2 \cdot \left(-3\right).
+fn factor_latex(value: &Value) -> String {
+    if value.latex.starts_with('-') {
+        format!("\\left({}\\right)", value.latex)
+    } else {
+        operand_latex(value)
+    }
+}
+
+// a value after + or -, with the sign of a negative single term folded in: eg. " - \frac{3}{4}"
+// for + -\frac{3}{4}, and " + i" for - -i.
+fn term_latex(sign: char, value: &Value) -> String {
+    let operand = operand_latex(value);
+    match (sign, operand.strip_prefix('-')) {
+        ('+', Some(abs)) => format!(" - {abs}"),
+        (_, Some(abs)) => format!(" + {abs}"),
+        (_, None) => format!(" {sign} {operand}"),
+    }
+}
+
+// the same summaries as the pretty format, as aligned equations for lecture notes.
+// Operations without a template print only the value.
+pub(super) fn display_latex(cmd: RawCommand, res: RawResult) -> String {
+    let op = cmd.op;
+    let ring = algebra_latex(&cmd.alg);
+
+    if op == Operation::Bezout || op == Operation::Trace {
+        let names = bezout_names(res.opers.len());
+        let terms: Vec<String> = names.iter().map(|(f, u)| format!("{u} {f}")).collect();
+        let fs: Vec<&str> = names.iter().map(|(f, _)| f.as_str()).collect();
+
+        let mut lines = vec![format!("{} &= H \\text{{ in }} {ring}, \\text{{ where}}", terms.join(" + "))];
+        for (k, (f, u)) in names.iter().enumerate() {
+            lines.push(format!("{u} &= {},", res.subs[k].latex));
+            lines.push(format!("{f} &= {},", res.opers[k].latex));
+        }
+        lines.push(format!("H &= \\gcd({}) = {}.", fs.join(", "), res.main.latex));
+
+        let table_str = latex_table(&res.table);
+        return format!("{table_str}\\begin{{aligned}}\n{}\n\\end{{aligned}}", lines.join(" \\\\\n"));
+    }
+    else if op == Operation::Solve {
+        let (a, b, c) = (&res.opers[0], &res.opers[1], &res.opers[2]);
+        return format!("{} X{} Y = {} \\iff X = {}{} T,\\ Y = {}{} T,\\ T \\in {ring}",
+            operand_latex(a), term_latex('+', b), c.latex, operand_latex(&res.main), term_latex('+', &res.subs[1]),
+            operand_latex(&res.subs[0]), term_latex('-', &res.subs[2]));
+    }
+    else if op == Operation::Contfrac {
+        let x = &res.opers[0].latex;
+        let lhs = if cmd.alg == Algebra::Q { x.clone() } else { format!("\\sqrt{{{x}}}") };
+        return format!("{lhs} = {}", res.main.latex);
+    }
+    else if op == Operation::Pell {
+        let d = &res.opers[0].latex;
+        let mut lines = vec![format!("{}^{{2}} - {d} \\cdot {}^{{2}} = {}",
+            factor_latex(&res.main), factor_latex(&res.subs[0]), res.subs[1].latex)];
+        if let (Some(x), Some(y)) = (res.subs.get(2), res.subs.get(3)) {
+            lines.push(format!("{}^{{2}} - {d} \\cdot {}^{{2}} = 1", factor_latex(x), factor_latex(y)));
+        }
+        return lines.join(", \\quad ");
+    }
+    else if op == Operation::Congruence {
+        let (a, b, n) = (&res.opers[0], &res.opers[1], &res.opers[2]);
+        return format!("{} X \\equiv {} \\pmod{{{}}} \\iff X \\equiv {} \\pmod{{{}}}",
+            operand_latex(a), b.latex, n.latex, res.main.latex, res.subs[0].latex);
+    }
+    else if op.is_arithmetic_function() {
+        let name = match op {
+            Operation::Totient => "\\varphi",
+            Operation::Sigma => "\\sigma",
+            Operation::NumDivisors => "\\tau",
+            Operation::Mobius => "\\mu",
+            Operation::Carmichael => "\\lambda",
+            _ => "\\operatorname{Div}",
+        };
+        return format!("{name}({}) = {}", res.opers[0].latex, res.main.latex);
+    }
+    else if op == Operation::Gcd || op == Operation::Lcm {
+        let operands: Vec<&str> = res.opers.iter().map(|x| x.latex.as_str()).collect();
+        let name = if op == Operation::Gcd { "\\gcd" } else { "\\operatorname{lcm}" };
+        return format!("{name}({}) = {} \\text{{ in }} {ring}", operands.join(", "), res.main.latex);
+    }
+    else if op == Operation::DivMod {
+        let lines = [
+            format!("F &= Q G + R \\text{{ in }} {ring}, \\text{{ where}}"),
+            format!("F &= {},", res.opers[0].latex),
+            format!("G &= {},", res.opers[1].latex),
+            format!("Q &= {},", res.main.latex),
+            format!("R &= {}.", res.subs[0].latex),
+        ];
+
+        return format!("\\begin{{aligned}}\n{}\n\\end{{aligned}}", lines.join(" \\\\\n"));
+    }
+
+    if matches!(op, Operation::Sqrt | Operation::Legendre | Operation::Jacobi) {
+        // in Z, the modulus is the second operand; F(p) has it in the ring.
+        let (modulus, ring) = match cmd.alg {
+            Algebra::F(p) => (p.to_string(), ring),
+            _ => (res.opers[1].latex.clone(), format!("\\mathbb{{Z}}/{}\\mathbb{{Z}}", res.opers[1].latex)),
+        };
+        let a = &res.opers[0].latex;
+        if op != Operation::Sqrt {
+            return format!("\\left(\\frac{{{a}}}{{{modulus}}}\\right) = {}", res.main.latex);
+        }
+        let roots = match res.subs.first() {
+            Some(other) => format!("{}, {}", res.main.latex, other.latex),
+            None => res.main.latex.clone(),
+        };
+        return format!("x^{{2}} = {a} \\iff x \\in \\{{{roots}\\}} \\text{{ in }} {ring}");
+    }
+
+    if op == Operation::Pow {
+        return format!("{}^{{{}}} = {} \\text{{ in }} {ring}", factor_latex(&res.opers[0]), res.opers[1].latex, res.main.latex);
+    }
+    if op == Operation::Dlog {
+        return format!("\\log_{{{}}} {} = {} \\text{{ in }} {ring}", res.opers[0].latex, res.opers[1].latex, res.main.latex);
+    }
+
+    let rhs = match op {
+        Operation::Add => term_latex('+', &res.opers[1]),
+        Operation::Sub => term_latex('-', &res.opers[1]),
+        Operation::Mul => format!(" \\cdot {}", factor_latex(&res.opers[1])),
+        Operation::Div => format!(" / {}", factor_latex(&res.opers[1])),
+        Operation::Mod => format!(" \\bmod {}", factor_latex(&res.opers[1])),
+        _ => return res.main.latex,
+    };
+
+    format!("{}{rhs} = {} \\text{{ in }} {ring}", operand_latex(&res.opers[0]), res.main.latex)
+}
+
+// the steps of a trace as an array, followed by a blank line. Empty if there are no steps.
+fn latex_table(table: &[Vec<Value>]) -> String {
+    if table.is_empty() {
+        return String::new();
+    }
+
+    let mut lines = vec!["\\text{dividend} & \\text{quotient} & \\text{divisor} & \\text{remainder} & s & t \\\\ \\hline".to_string()];
+    for row in table {
+        let cells: Vec<&str> = row.iter().map(|value| value.latex.as_str()).collect();
+        lines.push(format!("{} \\\\", cells.join(" & ")));
+    }
+
+    format!("\\begin{{array}}{{rrrrrr}}\n{}\n\\end{{array}}\n\n", lines.join("\n"))
+}
@@ -0,0 +1,82 @@
+use crate::customio::ParseError;
+
+// errors raised while evaluating a command, as opposed to ParseError, which is raised
+// while reading it. Operands are parsed lazily by the executors, so a ParseError can
+// still show up during evaluation; it is wrapped and printed as is.
+#[derive(PartialEq, Debug)]
+pub enum EvalError {
+    Parse(ParseError),
+
+    // eg. "7 mod 0". dividend is displayed in the algebra of the command.
+    ZeroDivisor { dividend: String },
+
+    // eg. 4 mod 6, where gcd = gcd(4, 6) = 2 is the obstruction.
+    NotInvertible { element: String, modulus: String, gcd: String },
+
+    // eg. X = 1 mod 4 and X = 2 mod 6, where gcd = gcd(4, 6) = 2 tells them apart.
+    // modulus is the one of the offending congruence, against all the previous ones.
+    Inconsistent { residue: String, modulus: String, gcd: String },
+
+    // eg. 2 X + 4 Y = 3, where gcd = gcd(a, b) = 2 does not divide rhs = 3.
+    // For a X = b mod n, gcd is gcd(a, n) and rhs is b.
+    NoSolution { a: String, b: String, gcd: String, rhs: String },
+
+    // eg. sqrt of 3 mod 7: 3 is no quadratic residue.
+    NotSquare { element: String, modulus: String },
+
+    // eg. dlog {2}{3} mod 7: the powers of 2 are 1, 2 and 4 only.
+    NotInSubgroup { element: String, base: String, modulus: String },
+
+    // eg. "bezout" in F(7).
+    Unsupported { op: String, alg: String },
+
+    // an operation the algebra supports, applied outside its domain, eg. factor of 0.
+    // reason says what the operand fails.
+    Domain { op: String, reason: String },
+
+    // eg. dlog mod a prime p, where p - 1 has a huge prime factor. what names the quantity
+    // past its limit, and the computation is refused before it starts.
+    TooLarge { what: String, limit: String },
+
+    // a panic caught in the arithmetic layer. It always indicates a bug.
+    Internal { msg: String },
+}
+
+impl From<ParseError> for EvalError {
+    fn from(err: ParseError) -> Self {
+        EvalError::Parse(err)
+    }
+}
+
+impl EvalError {
+    pub fn print(&self){
+        println!("Error: {}\n", self.message());
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            EvalError::Parse(err) => err.message(),
+            EvalError::ZeroDivisor { dividend } =>
+                format!("Cannot divide {dividend} by zero."),
+            EvalError::NotInvertible { element, modulus, gcd } =>
+                format!("{element} is not invertible mod {modulus}, since their gcd is {gcd}."),
+            EvalError::Inconsistent { residue, modulus, gcd } =>
+                format!("X = {residue} (mod {modulus}) contradicts the previous congruences, \
+                    since the residues differ mod {gcd}, a common factor of the moduli."),
+            EvalError::NoSolution { a, b, gcd, rhs } =>
+                format!("There is no solution, since gcd({a}, {b}) = {gcd} does not divide {rhs}."),
+            EvalError::NotSquare { element, modulus } =>
+                format!("{element} is not a square mod {modulus}."),
+            EvalError::NotInSubgroup { element, base, modulus } =>
+                format!("{element} is not in the subgroup generated by {base} mod {modulus}, so it has no logarithm to that base."),
+            EvalError::Unsupported { op, alg } =>
+                format!("Operation {op} is not supported in [{alg}]."),
+            EvalError::Domain { op, reason } =>
+                format!("Cannot compute {op}, since {reason}."),
+            EvalError::TooLarge { what, limit } =>
+                format!("{what} is too large to proceed: the limit is {limit}."),
+            EvalError::Internal { msg } =>
+                format!("Internal error, please report it as a bug: {msg}"),
+        }
+    }
+}
@@ -1,67 +1,440 @@
 use super::datatype::*;
+use super::error::EvalError;
 use crate::arithmetic::*;
 use crate::customio::*;
+use crate::Duo;
 
-pub(super) fn execute_ring<R>(cmd: &RawCommand) -> Result<RawResult, ParseError>
-where R: EuclideanRing + Parse + SimpleDisplay {
+pub(super) fn execute_ring<R>(cmd: &RawCommand) -> Result<RawResult, EvalError>
+where R: EuclideanRing + Parse + SimpleDisplay + JsonDisplay + LatexDisplay {
+    execute_euclidean(&RingOf::<R>::default(), |input| R::parse_in(input, &cmd.scope), cmd)
+}
+
+// the operations of a Euclidean ring, whose operands are read by read. F(p)[x] comes here
+// too, with Prime as the ring, see EuclideanDomain.
+fn execute_euclidean<D, P>(ring: &D, read: P, cmd: &RawCommand) -> Result<RawResult, EvalError>
+where D: EuclideanDomain, D::Elem: SimpleDisplay + JsonDisplay + LatexDisplay,
+      P: Fn(&str) -> Result<D::Elem, ParseError> {
     let op = cmd.op;
-    let str_operand1 = cmd.operand1.as_str();
-    let str_operand2 = cmd.operand2.as_str();
-    let operand1 = R::parse(str_operand1)?;
-    let operand2 = R::parse(str_operand2)?;
-    
-    if op == Operation::Bezout {
-        let (u, v, gcd) = R::unwrapped_bezout(&operand1, &operand2);
-
-        Ok(RawResult{
-            oper1: operand1.simple_display(),
-            oper2: operand2.simple_display(),
-            main: gcd.simple_display(),
-            sub1: Some(u.simple_display()),
-            sub2: Some(v.simple_display()),
-        })
-    }
-    else if op == Operation::DivMod {
-        if operand2 == R::zero() {
-            return Err(ParseError::ZeroDenom);
+    // the generic rings know nothing else.
+    if !matches!(op, Operation::Eval | Operation::Add | Operation::Sub | Operation::Mul | Operation::Mod
+        | Operation::DivMod | Operation::Bezout | Operation::Trace | Operation::Gcd | Operation::Lcm
+        | Operation::Crt | Operation::Solve) {
+        return Err(unsupported(cmd));
+    }
+
+    let mut operands: Vec<D::Elem> = Vec::new();
+    for str_operand in &cmd.operands {
+        operands.push(read(str_operand)?);
+    }
+    if op == Operation::Crt {
+        return execute_crt(ring, &operands);
+    }
+    if matches!(op, Operation::Bezout | Operation::Gcd | Operation::Lcm) {
+        return Ok(execute_gcd(ring, op, &operands));
+    }
+    if op == Operation::Solve {
+        return execute_solve(ring, &operands);
+    }
+    if op == Operation::Eval {
+        return Ok(value_result(Value::of(&operands[0]), None));
+    }
+
+    let (operand1, operand2) = (&operands[0], &operands[1]);
+    let opers = vec![Value::of(operand1), Value::of(operand2)];
+    if op == Operation::Trace {
+        let (coeffs, gcd) = ring.bezout_all(&operands);
+        let steps = ring.euclid_trace(&Duo { first: operand1.clone(), second: operand2.clone() });
+
+        return Ok(RawResult {
+            table: steps.iter().map(trace_row).collect(),
+            ..RawResult::new(opers, Value::of(&gcd), coeffs.iter().map(Value::of).collect())
+        });
+    }
+
+    if op == Operation::DivMod || op == Operation::Mod {
+        let Some(duo) = ring.try_divmod(operand1, operand2) else {
+            return Err(EvalError::ZeroDivisor { dividend: operand1.simple_display() });
+        };
+        if op == Operation::Mod {
+            return Ok(RawResult::new(opers, Value::of(&duo.second), Vec::new()));
         }
-        let duo = R::divmod(&operand1, &operand2);
-        let quo = duo.first; let rem= duo.second;
+        return Ok(RawResult::new(opers, Value::of(&duo.first), vec![Value::of(&duo.second)]));
+    }
+
+    let res = match op {
+        Operation::Add => ring.add(operand1, operand2),
+        Operation::Sub => ring.subtract(operand1, operand2),
+        Operation::Mul => ring.multiply(operand1, operand2),
+        _ => return Err(unsupported(cmd)),
+    };
+
+    Ok(RawResult::new(opers, Value::of(&res), Vec::new()))
+}
 
-        Ok(RawResult {
-            oper1: operand1.simple_display(),
-            oper2: operand2.simple_display(),
-            main: quo.simple_display(),
-            sub1: Some(rem.simple_display()),
-            sub2: None,
-        })
+pub(super) fn execute_z(cmd: &RawCommand) -> Result<RawResult, EvalError> {
+    let op = cmd.op;
+    if matches!(op, Operation::Sqrt | Operation::Legendre | Operation::Jacobi) {
+        return execute_residue(cmd);
     }
-    else {
-        let res = match op {
-            Operation::Add => R::add(&operand1, &operand2),
-            Operation::Sub => R::subtract(&operand1, &operand2),
-            Operation::Mul => R::multiply(&operand1, &operand2),
-            Operation::Mod => R::divmod(&operand1, &operand2).second,
-            _ => return Err(ParseError::Op),
+    if op.is_arithmetic_function() {
+        return execute_arithmetic(cmd);
+    }
+    if op == Operation::Congruence {
+        return execute_congruence(cmd);
+    }
+    if op == Operation::Contfrac || op == Operation::Pell {
+        return execute_pell(cmd);
+    }
+    if op != Operation::Factor && op != Operation::IsPrime && op != Operation::NextPrime {
+        return execute_ring::<Integer>(cmd);
+    }
+
+    let operand = Integer::parse_in(cmd.operands[0].as_str(), &cmd.scope)?;
+    let mut subs = Vec::new();
+    let main = match op {
+        Operation::IsPrime => {
+            let is_prime = operand.is_prime();
+            if is_prime {
+                subs.push(Value::boolean(operand.primality_is_proven()));
+            }
+            Value::boolean(is_prime)
+        }
+        Operation::NextPrime => Value::of(&operand.next_prime()),
+        _ => {
+            let factorization = operand.factor().map_err(|m| unsplit_error(&m, &operand.simple_display()))?;
+            return factor_result(&operand, factorization);
+        }
+    };
+
+    Ok(RawResult::new(vec![Value::of(&operand)], main, subs))
+}
+
+// at most this many divisors are listed; their number is checked before the list is built.
+const MAX_LISTED_DIVISORS: usize = 10000;
+
+// totient and its kin of {n}, for n > 0.
+fn execute_arithmetic(cmd: &RawCommand) -> Result<RawResult, EvalError> {
+    let n = Integer::parse_in(cmd.operands[0].as_str(), &cmd.scope)?;
+    let of_n = n.simple_display();
+    if cmd.op == Operation::Divisors
+        && let Some(count) = n.num_divisors().map_err(|m| unsplit_error(&m, &of_n))?
+        && count.number > BigInt::from(MAX_LISTED_DIVISORS) {
+        return Err(EvalError::TooLarge {
+            what: format!("The list of the {} divisors of {}", count.simple_display(), n.simple_display()),
+            limit: MAX_LISTED_DIVISORS.to_string(),
+        });
+    }
+
+    let main = match cmd.op {
+        Operation::Totient => n.totient().map(|x| x.map(|x| Value::of(&x))),
+        Operation::Sigma => n.sigma().map(|x| x.map(|x| Value::of(&x))),
+        Operation::NumDivisors => n.num_divisors().map(|x| x.map(|x| Value::of(&x))),
+        Operation::Mobius => n.mobius().map(|x| x.map(|x| Value::of(&Integer::new(x)))),
+        Operation::Carmichael => n.carmichael().map(|x| x.map(|x| Value::of(&x))),
+        _ => n.divisors().map(|xs| xs.map(|xs| Value::list(&xs))),
+    };
+    let Some(main) = main.map_err(|m| unsplit_error(&m, &of_n))? else {
+        return Err(EvalError::Domain {
+            op: format!("{} of {}", cmd.op, n.simple_display()),
+            reason: format!("{} is not positive", n.simple_display()),
+        });
+    };
+
+    Ok(RawResult::new(vec![Value::of(&n)], main, Vec::new()))
+}
+
+// contfrac of sqrt(d), with the length of its period as sub, and pell {d}.
+// subs of pell are y and the sign of x^2 - d y^2, then, if it is -1,
+// the fundamental solution of x^2 - d y^2 = 1: (x^2 + d y^2, 2 x y).
+fn execute_pell(cmd: &RawCommand) -> Result<RawResult, EvalError> {
+    let d = Integer::parse_in(cmd.operands[0].as_str(), &cmd.scope)?;
+    // sqrt(d) is rational or not real otherwise, and x^2 - d y^2 has finitely many values.
+    let not_periodic = || EvalError::Domain {
+        op: format!("{} of {}", cmd.op, d.simple_display()),
+        reason: if d.is_positive() {
+            format!("{} is a square", d.simple_display())
+        } else {
+            format!("{} is not positive", d.simple_display())
+        },
+    };
+
+    let too_long = |_| EvalError::TooLarge {
+        what: format!("The period of the continued fraction of sqrt({})", d.simple_display()),
+        limit: format!("{MAX_PERIOD} terms"),
+    };
+
+    let (main, subs) = if cmd.op == Operation::Contfrac {
+        let (a_0, period) = d.sqrt_contfrac().map_err(too_long)?.ok_or_else(not_periodic)?;
+        let length = Integer::new(period.len() as int);
+        (contfrac_value(&a_0, &period, true), vec![Value::of(&length)])
+    } else {
+        let (x, y, sign) = d.pell().map_err(too_long)?.ok_or_else(not_periodic)?;
+        let mut subs = vec![Value::of(&y), Value::of(&Integer::new(sign))];
+        if sign == -1 {
+            let x_plus = Integer::add(&Integer::multiply(&x, &x), &Integer::multiply(&d, &Integer::multiply(&y, &y)));
+            let y_plus = Integer::multiply(&Integer::new(2), &Integer::multiply(&x, &y));
+            subs.push(Value::of(&x_plus));
+            subs.push(Value::of(&y_plus));
+        }
+        (Value::of(&x), subs)
+    };
+
+    Ok(RawResult::new(vec![Value::of(&d)], main, subs))
+}
+
+// [a_0; a_1, ..., a_n], or [a_0; (a_1, ..., a_r)] with a period.
+fn contfrac_value(a_0: &Integer, rest: &[Integer], periodic: bool) -> Value {
+    let texts: Vec<String> = rest.iter().map(|a| a.simple_display()).collect();
+    let (text, latex) = match (rest.is_empty(), periodic) {
+        (true, _) => (format!("[{}]", a_0.simple_display()), format!("[{}]", a_0.latex_display())),
+        (false, false) => (format!("[{}; {}]", a_0.simple_display(), texts.join(", ")),
+            format!("[{}; {}]", a_0.latex_display(), texts.join(", "))),
+        (false, true) => (format!("[{}; ({})]", a_0.simple_display(), texts.join(", ")),
+            format!("[{}; \\overline{{{}}}]", a_0.latex_display(), texts.join(", "))),
+    };
+
+    let key = if periodic { "period" } else { "terms" };
+    let json = Json::object(vec![
+        ("text", Json::Str(text.clone())),
+        ("integer_part", a_0.json_display()),
+        (key, Json::Array(rest.iter().map(|a| a.json_display()).collect())),
+    ]);
+
+    Value { text, json, latex }
+}
+
+// sqrt, legendre and jacobi of {a} mod {m}. m is prime, and odd for legendre;
+// jacobi takes any odd m > 0.
+fn execute_residue(cmd: &RawCommand) -> Result<RawResult, EvalError> {
+    if cmd.operands.len() != 2 {
+        return Err(ParseError::Arity.into());
+    }
+    let a = Integer::parse_in(cmd.operands[0].as_str(), &cmd.scope)?;
+    let m = Integer::parse_in(cmd.operands[1].as_str(), &cmd.scope)?;
+
+    if cmd.op == Operation::Jacobi {
+        let Some(symbol) = a.jacobi(&m) else {
+            return Err(EvalError::Domain {
+                op: format!("the Jacobi symbol ({}/{})", a.simple_display(), m.simple_display()),
+                reason: format!("{} is not odd and positive", m.simple_display()),
+            });
+        };
+        return Ok(RawResult::new(vec![Value::of(&a), Value::of(&m)], Value::of(&Integer::new(symbol)), Vec::new()));
+    }
+
+    let Some(p) = Prime::try_from_integer(&m) else {
+        return Err(ParseError::NotPrime.into());
+    };
+    let (main, subs) = residue_result(cmd, &p, &a)?;
+    // x^2 = a reads better with a reduced, unlike the symbol (a/p).
+    let a = if cmd.op == Operation::Sqrt { p.modulo(&a) } else { a };
+
+    Ok(RawResult::new(vec![Value::of(&a), Value::of(&m)], main, subs))
+}
+
+// sqrt or legendre of a in F(p). subs has the other root of a sqrt, if it is distinct.
+fn residue_result(cmd: &RawCommand, p: &Prime, a: &Integer) -> Result<(Value, Vec<Value>), EvalError> {
+    if cmd.op == Operation::Sqrt {
+        let Some(r) = p.sqrt(a) else {
+            return Err(EvalError::NotSquare { element: p.modulo(a).simple_display(), modulus: p.p.simple_display() });
         };
+        let other = p.neg(&r);
+        let subs = if other == r { Vec::new() } else { vec![Value::of(&other)] };
+        return Ok((Value::of(&r), subs));
+    }
+
+    let Some(symbol) = p.legendre(a) else {
+        return Err(EvalError::Domain {
+            op: format!("the Legendre symbol ({}/2)", a.simple_display()),
+            reason: "2 is not an odd prime".to_string(),
+        });
+    };
+
+    Ok((Value::of(&Integer::new(symbol)), Vec::new()))
+}
+
+pub(super) fn execute_q(cmd: &RawCommand) -> Result<RawResult, EvalError> {
+    if cmd.op != Operation::Contfrac {
+        return execute_field::<Rational>(cmd);
+    }
+
+    let x = Rational::parse_in(cmd.operands[0].as_str(), &cmd.scope)?;
+    let terms = x.contfrac();
+    let convergents = Rational::convergents(&terms);
+
+    let main = contfrac_value(&terms[0], &terms[1..], false);
+    Ok(RawResult::new(vec![Value::of(&x)], main, vec![Value::list(&convergents)]))
+}
+
+pub(super) fn execute_qpol(cmd: &RawCommand) -> Result<RawResult, EvalError> {
+    if cmd.op != Operation::Factor {
+        return execute_ring::<QPol>(cmd);
+    }
+
+    let operand = QPol::parse_in(cmd.operands[0].as_str(), &cmd.scope)?;
+    factor_result(&operand, operand.factor())
+}
+
+pub(super) fn execute_zi(cmd: &RawCommand) -> Result<RawResult, EvalError> {
+    if cmd.op != Operation::Factor {
+        return execute_ring::<GaussInteger>(cmd);
+    }
+
+    let operand = GaussInteger::parse_in(cmd.operands[0].as_str(), &cmd.scope)?;
+    let of_norm = format!("the norm of {}", operand.simple_display());
+    let factorization = operand.factor().map_err(|m| unsplit_error(&m, &of_norm))?;
+    factor_result(&operand, factorization)
+}
+
+// a factorization is None only for zero.
+fn factor_result<T: SimpleDisplay + JsonDisplay + LatexDisplay>(operand: &T, factorization: Option<Factorization<T>>)
+-> Result<RawResult, EvalError> {
+    let Some(factorization) = factorization else {
+        return Err(EvalError::Domain {
+            op: "the factorization of 0".to_string(),
+            reason: "0 is no product of irreducibles and a unit".to_string(),
+        });
+    };
+
+    Ok(RawResult::new(vec![Value::of(operand)], Value::of(&factorization), vec![Value::of(&factorization.unit)]))
+}
+
+// gcd, lcm and bezout of two or more operands. subs are the U_k of bezout.
+fn execute_gcd<D>(ring: &D, op: Operation, operands: &[D::Elem]) -> RawResult
+where D: EuclideanDomain, D::Elem: SimpleDisplay + JsonDisplay + LatexDisplay {
+    let (main, subs) = match op {
+        Operation::Lcm => (operands.iter().fold(ring.one(), |l, f| ring.lcm(&l, f)), Vec::new()),
+        Operation::Gcd => (ring.bezout_all(operands).1, Vec::new()),
+        _ => {
+            let (coeffs, gcd) = ring.bezout_all(operands);
+            (gcd, coeffs)
+        }
+    };
+
+    RawResult::new(operands.iter().map(Value::of).collect(), Value::of(&main), subs.iter().map(Value::of).collect())
+}
+
+// a X + b Y = c. subs are Y, then the steps s and r of X = x + s T, Y = y - r T.
+fn execute_solve<D>(ring: &D, operands: &[D::Elem]) -> Result<RawResult, EvalError>
+where D: EuclideanDomain, D::Elem: SimpleDisplay + JsonDisplay + LatexDisplay {
+    let (a, b, c) = (&operands[0], &operands[1], &operands[2]);
+    if *a == ring.zero() && *b == ring.zero() {
+        return Err(solve_zero_error());
+    }
+
+    match ring.solve_linear(a, b, c) {
+        Ok(sol) => Ok(solve_result(operands, &sol)),
+        Err(gcd) => Err(EvalError::NoSolution {
+            a: a.simple_display(),
+            b: b.simple_display(),
+            gcd: gcd.simple_display(),
+            rhs: c.simple_display(),
+        }),
+    }
+}
+
+fn solve_result<T: SimpleDisplay + JsonDisplay + LatexDisplay>(operands: &[T], sol: &LinearSolution<T>) -> RawResult {
+    let subs = vec![Value::of(&sol.y), Value::of(&sol.step_x), Value::of(&sol.step_y)];
+    RawResult::new(operands.iter().map(Value::of).collect(), Value::of(&sol.x), subs)
+}
+
+// a X + b Y = c with a = b = 0 holds for all X and Y, or for none.
+fn solve_zero_error() -> EvalError {
+    EvalError::Domain {
+        op: "the solutions of a X + b Y = c".to_string(),
+        reason: "a = b = 0 leaves X and Y undetermined".to_string(),
+    }
+}
+
+// solutions of a congruence are listed up to this many; past it, only their number is given.
+const MAX_LISTED_SOLUTIONS: usize = 100;
+
+// a X = b mod n, n > 0. As a X + n Y = b, the solutions are X = x mod m, with m = n / g
+// and g = gcd(a, n); mod n, they are the g numbers x + k m. subs are m, g, and the list
+// of solutions mod n if it is short enough.
+fn execute_congruence(cmd: &RawCommand) -> Result<RawResult, EvalError> {
+    let mut operands: Vec<Integer> = Vec::new();
+    for str_operand in &cmd.operands {
+        operands.push(Integer::parse_in(str_operand, &cmd.scope)?);
+    }
+    let (a, b, n) = (&operands[0], &operands[1], &operands[2]);
+    if !n.is_positive() {
+        return Err(EvalError::Domain {
+            op: format!("the solutions of a congruence mod {}", n.simple_display()),
+            reason: format!("{} is not positive", n.simple_display()),
+        });
+    }
+
+    let sol = match Integer::solve_linear(a, n, b) {
+        Ok(sol) => sol,
+        Err(gcd) => return Err(EvalError::NoSolution {
+            a: a.simple_display(),
+            b: n.simple_display(),
+            gcd: gcd.simple_display(),
+            rhs: b.simple_display(),
+        }),
+    };
+    // with a = 0 mod n, gcd(a, n) = n.
+    let m = sol.step_x.regular().second;
+    let x = Integer::divmod(&sol.x, &m).second;
+    let count = Integer::divmod(n, &m).first;
+
+    let mut subs = vec![Value::of(&m), Value::of(&count)];
+    if count.number <= BigInt::from(MAX_LISTED_SOLUTIONS) {
+        let mut solutions: Vec<Integer> = Vec::new();
+        let mut k = x.clone();
+        while k < *n {
+            solutions.push(k.clone());
+            k = Integer::add(&k, &m);
+        }
+        subs.push(Value::list(&solutions));
+    }
+
+    Ok(RawResult::new(operands.iter().map(Value::of).collect(), Value::of(&x), subs))
+}
+
+// operands come in pairs {residue}{modulus}. The moduli must be nonzero.
+fn execute_crt<D>(ring: &D, operands: &[D::Elem]) -> Result<RawResult, EvalError>
+where D: EuclideanDomain, D::Elem: SimpleDisplay + JsonDisplay + LatexDisplay {
+    let mut pairs: Vec<Duo<D::Elem>> = Vec::new();
+    for pair in operands.chunks(2) {
+        if pair[1] == ring.zero() {
+            return Err(EvalError::Domain {
+                op: format!("X with X = {} mod 0", pair[0].simple_display()),
+                reason: "the moduli of a congruence must be nonzero".to_string(),
+            });
+        }
+        pairs.push(Duo { first: pair[0].clone(), second: pair[1].clone() });
+    }
 
-        Ok(RawResult {
-            oper1: operand1.simple_display(),
-            oper2: operand2.simple_display(),
-            main: res.simple_display(),
-            sub1: None,
-            sub2: None
-        })
+    match ring.crt(&pairs) {
+        Ok(duo) => {
+            let opers = operands.iter().map(Value::of).collect();
+            Ok(RawResult::new(opers, Value::of(&duo.first), vec![Value::of(&duo.second)]))
+        }
+        Err((k, gcd)) => Err(EvalError::Inconsistent {
+            residue: pairs[k].first.simple_display(),
+            modulus: pairs[k].second.simple_display(),
+            gcd: gcd.simple_display(),
+        }),
     }
 }
 
-pub(super) fn execute_field<F>(cmd: &RawCommand) -> Result<RawResult, ParseError> 
-where F: Field + Parse + SimpleDisplay {
+pub(super) fn execute_field<F>(cmd: &RawCommand) -> Result<RawResult, EvalError> 
+where F: Field + Parse + SimpleDisplay + JsonDisplay + LatexDisplay {
     let op = cmd.op;
-    let str_operand1 = cmd.operand1.as_str();
-    let str_operand2 = cmd.operand2.as_str();
-    let operand1 = F::parse(str_operand1)?;
-    let operand2 = F::parse(str_operand2)?;
+    if op == Operation::Eval {
+        let value = F::parse_in(cmd.operands[0].as_str(), &cmd.scope)?;
+        return Ok(value_result(Value::of(&value), None));
+    }
+    if !op.accepts(2) {
+        return Err(unsupported(cmd));
+    }
+
+    let str_operand1 = cmd.operands[0].as_str();
+    let str_operand2 = cmd.operands[1].as_str();
+    let operand1 = F::parse_in(str_operand1, &cmd.scope)?;
+    let operand2 = F::parse_in(str_operand2, &cmd.scope)?;
 
     //pay special attention to division. zero cannot be a divisor.
     let res = match op {
@@ -69,40 +442,105 @@ where F: Field + Parse + SimpleDisplay {
         Operation::Sub => F::subtract(&operand1, &operand2),
         Operation::Mul => F::multiply(&operand1, &operand2),
         Operation::Div => {
-            if F::equal(&operand2, &F::zero()){
-                return Err(ParseError::ZeroDenom);
-            }
-            F::divide(&operand1, &operand2)
+            let Some(quot) = F::divide(&operand1, &operand2) else {
+                return Err(EvalError::ZeroDivisor { dividend: operand1.simple_display() });
+            };
+            quot
         }
-        _ => return Err(ParseError::Op),
+        _ => return Err(unsupported(cmd)),
     };
 
-    Ok(RawResult {
-        oper1: operand1.simple_display(),
-        oper2: operand2.simple_display(),
-        main: res.simple_display(),
-        sub1: None,
-        sub2: None
-    })
+    Ok(RawResult::new(vec![Value::of(&operand1), Value::of(&operand2)], Value::of(&res), Vec::new()))
 }
 
-pub(super) fn execute_f(cmd: &RawCommand) -> Result<RawResult, ParseError> {
-    let Algebra::F(p_int) = cmd.alg else {
-        panic!()
-    };
-    
-    let Some(p) = Prime::try_new(p_int) else {
-        return Err(ParseError::NotPrime);
+pub(super) fn execute_f(p_int: &BigInt, cmd: &RawCommand) -> Result<RawResult, EvalError> {
+    let Some(p) = Prime::try_from_integer(&Integer::from_bigint(p_int.clone())) else {
+        return Err(ParseError::NotPrime.into());
     };
 
     let op = cmd.op;
-    let str_operand1 = cmd.operand1.as_str();
-    let str_operand2 = cmd.operand2.as_str();
-    let raw_operand1 = Integer::parse(str_operand1)?;
-    let raw_operand2 = Integer::parse(str_operand2)?;
+    if op == Operation::Eval {
+        let value = evaluate(&p, cmd.operands[0].as_str(), &cmd.scope)?;
+        return Ok(value_result(Value::of(&value), None));
+    }
+    // the unit group needs the factorization of p - 1.
+    let of_group = format!("{} - 1", p.p.simple_display());
+    if op == Operation::PrimRoot {
+        let g = p.primroot().map_err(|m| unsplit_error(&m, &of_group))?;
+        return Ok(RawResult::new(Vec::new(), Value::of(&g), Vec::new()));
+    }
+
+    // quadratic residues. In F(p), jacobi is legendre.
+    if matches!(op, Operation::Sqrt | Operation::Legendre | Operation::Jacobi) {
+        if cmd.operands.len() != 1 {
+            return Err(ParseError::Arity.into());
+        }
+        let x = evaluate(&p, cmd.operands[0].as_str(), &cmd.scope)?;
+        let (main, subs) = residue_result(cmd, &p, &x)?;
+
+        return Ok(RawResult::new(vec![Value::of(&x)], main, subs));
+    }
+
+    // the unit group. The exponent of pow is an integer, not an element of F(p). It may still
+    // use the names bound in F(p), like a dlog in $n, through their representatives in [0, p-1].
+    if matches!(op, Operation::Pow | Operation::Order | Operation::IsPrimRoot | Operation::Dlog) {
+        let x = evaluate(&p, cmd.operands[0].as_str(), &cmd.scope)?;
+        let (opers, main) = match op {
+            Operation::Pow => {
+                let e = Integer::parse_in(cmd.operands[1].as_str(), &cmd.scope)?;
+                let Some(res) = p.pow(&x, &e) else {
+                    return Err(EvalError::ZeroDivisor { dividend: "1".to_string() });
+                };
+                (vec![Value::of(&x), Value::of(&e)], Value::of(&res))
+            }
+            Operation::Order => {
+                let Some(order) = p.order(&x).map_err(|m| unsplit_error(&m, &of_group))? else {
+                    return Err(EvalError::Domain {
+                        op: "the order of 0".to_string(),
+                        reason: "0 is not in the multiplicative group".to_string(),
+                    });
+                };
+                (vec![Value::of(&x)], Value::of(&order))
+            }
+            Operation::Dlog => {
+                let h = evaluate(&p, cmd.operands[1].as_str(), &cmd.scope)?;
+                if p.modulo(&x).is_zero() {
+                    return Err(EvalError::Domain {
+                        op: "the logarithm to the base 0".to_string(),
+                        reason: "0 is not in the multiplicative group".to_string(),
+                    });
+                }
+                let log = match p.dlog(&x, &h) {
+                    Ok(log) => log,
+                    Err(q) => return Err(EvalError::TooLarge {
+                        what: format!("The factor {} of the order of {}", q.simple_display(), x.simple_display()),
+                        limit: format!("2^{}", MAX_DLOG_FACTOR.trailing_zeros()),
+                    }),
+                };
+                let Some(log) = log else {
+                    return Err(EvalError::NotInSubgroup {
+                        element: h.simple_display(),
+                        base: x.simple_display(),
+                        modulus: p.p.simple_display(),
+                    });
+                };
+                (vec![Value::of(&x), Value::of(&h)], Value::of(&log))
+            }
+            _ => {
+                let is_primroot = p.is_primroot(&x).map_err(|m| unsplit_error(&m, &of_group))?;
+                (vec![Value::of(&x)], Value::boolean(is_primroot))
+            }
+        };
+
+        return Ok(RawResult::new(opers, main, Vec::new()));
+    }
+
+    if !op.accepts(2) {
+        return Err(unsupported(cmd));
+    }
 
-    let operand1 = p.modulo(&raw_operand1);
-    let operand2 = p.modulo(&raw_operand2);
+    let operand1 = evaluate(&p, cmd.operands[0].as_str(), &cmd.scope)?;
+    let operand2 = evaluate(&p, cmd.operands[1].as_str(), &cmd.scope)?;
 
     //pay special attention to division. zero cannot be a divisor.
     let res = match op {
@@ -110,82 +548,249 @@ pub(super) fn execute_f(cmd: &RawCommand) -> Result<RawResult, ParseError> {
         Operation::Sub => p.subtract(&operand1, &operand2),
         Operation::Mul => p.multiply(&operand1, &operand2),
         Operation::Div => {
-            if operand2 == Integer::zero() {
-                return Err(ParseError::ZeroDenom);
-            }
-            p.divide(&operand1, &operand2)
+            let Some(inv) = p.try_inv(&operand2) else {
+                return Err(EvalError::ZeroDivisor { dividend: operand1.simple_display() });
+            };
+            p.multiply(&operand1, &inv)
         }
-        _ => return Err(ParseError::Op),
+        _ => return Err(unsupported(cmd)),
     };
 
-    Ok(RawResult {
-        oper1: operand1.simple_display(),
-        oper2: operand2.simple_display(),
-        main: res.simple_display(),
-        sub1: None,
-        sub2: None
-    })    
+    Ok(RawResult::new(vec![Value::of(&operand1), Value::of(&operand2)], Value::of(&res), Vec::new()))    
 }
 
-pub(super) fn execute_fpol(cmd: &RawCommand) -> Result<RawResult, ParseError> {
-    let Algebra::FPol(p_int) = cmd.alg else {
-        panic!()
+pub(super) fn execute_fpol(p_int: &BigInt, cmd: &RawCommand) -> Result<RawResult, EvalError> {
+    let Some(p) = Prime::try_from_integer(&Integer::from_bigint(p_int.clone())) else {
+        return Err(ParseError::NotPrime.into());
     };
-    
-    let Some(p) = Prime::try_new(p_int) else {
-        return Err(ParseError::NotPrime);
+
+    if cmd.op == Operation::Factor {
+        let operand = evaluate(&PolMod(&p), cmd.operands[0].as_str(), &cmd.scope)?;
+        return factor_result(&operand, p.factorpol(&operand));
+    }
+
+    execute_euclidean(&p, |input| evaluate(&PolMod(&p), input, &cmd.scope), cmd)
+}
+
+pub(super) fn execute_zmod(n_int: &BigInt, cmd: &RawCommand) -> Result<RawResult, EvalError> {
+    let Some(n) = Modulus::try_from_integer(&Integer::from_bigint(n_int.clone())) else {
+        return Err(ParseError::Modulus.into());
     };
 
     let op = cmd.op;
-    let str_operand1 = cmd.operand1.as_str();
-    let str_operand2 = cmd.operand2.as_str();
-    let raw_operand1 = ZPol::parse(str_operand1)?;
-    let raw_operand2 = ZPol::parse(str_operand2)?;
-
-    let operand1 = p.modpol(&raw_operand1);
-    let operand2 = p.modpol(&raw_operand2);
-
-    if op == Operation::Bezout {
-        let (u, v, gcd) = p.unwrapped_bezoutpol(&operand1, &operand2);
-
-        Ok(RawResult{
-            oper1: operand1.simple_display(),
-            oper2: operand2.simple_display(),
-            main: gcd.simple_display(),
-            sub1: Some(u.simple_display()),
-            sub2: Some(v.simple_display()),
-        })
-    }
-    else if op == Operation::DivMod {
+    let operand1 = evaluate(&n, cmd.operands[0].as_str(), &cmd.scope)?;
+
+    if op == Operation::Eval {
+        return Ok(value_result(Value::of(&operand1), None));
+    }
+
+    // unary queries. main is "true" or "false"; subs has the inverse of a unit,
+    // or the witness y with x * y = 0 of a zero divisor.
+    if op == Operation::IsUnit {
+        let inv = n.try_inv(&operand1).ok();
+
+        let subs = inv.iter().map(Value::of).collect();
+        return Ok(RawResult::new(vec![Value::of(&operand1)], Value::boolean(inv.is_some()), subs))
+    }
+    else if op == Operation::IsZeroDivisor {
+        let witness = n.zero_divisor_witness(&operand1);
+
+        let subs = witness.iter().map(Value::of).collect();
+        return Ok(RawResult::new(vec![Value::of(&operand1)], Value::boolean(witness.is_some()), subs))
+    }
+
+    if !op.accepts(2) {
+        return Err(unsupported(cmd));
+    }
+
+    // as in F(p), the exponent is an integer, not an element of Z/nZ, read with the names of Z/nZ.
+    if op == Operation::Pow {
+        let e = Integer::parse_in(cmd.operands[1].as_str(), &cmd.scope)?;
+        let res = match n.pow(&operand1, &e) {
+            Ok(res) => res,
+            Err(gcd) => return Err(EvalError::NotInvertible {
+                element: operand1.simple_display(),
+                modulus: n.to_integer().simple_display(),
+                gcd: gcd.simple_display(),
+            }),
+        };
+
+        return Ok(RawResult::new(vec![Value::of(&operand1), Value::of(&e)], Value::of(&res), Vec::new()))
+    }
+
+    let operand2 = evaluate(&n, cmd.operands[1].as_str(), &cmd.scope)?;
+
+    let res = match op {
+        Operation::Add => n.add(&operand1, &operand2),
+        Operation::Sub => n.subtract(&operand1, &operand2),
+        Operation::Mul => n.multiply(&operand1, &operand2),
+        Operation::Div => {
+            if operand2.is_zero() {
+                return Err(EvalError::ZeroDivisor { dividend: operand1.simple_display() });
+            }
+            match n.divide(&operand1, &operand2) {
+                Ok(res) => res,
+                Err(gcd) => return Err(EvalError::NotInvertible {
+                    element: operand2.simple_display(),
+                    modulus: n.to_integer().simple_display(),
+                    gcd: gcd.simple_display(),
+                }),
+            }
+        }
+        _ => return Err(unsupported(cmd)),
+    };
+
+    Ok(RawResult::new(vec![Value::of(&operand1), Value::of(&operand2)], Value::of(&res), Vec::new()))
+}
+
+pub(super) fn execute_zmodpol(n_int: &BigInt, cmd: &RawCommand) -> Result<RawResult, EvalError> {
+    let Some(n) = Modulus::try_from_integer(&Integer::from_bigint(n_int.clone())) else {
+        return Err(ParseError::Modulus.into());
+    };
+
+    let op = cmd.op;
+    let operand1 = evaluate(&PolMod(&n), cmd.operands[0].as_str(), &cmd.scope)?;
+
+    if op == Operation::Eval {
+        return Ok(value_result(Value::of(&operand1), None));
+    }
+    if !op.accepts(2) {
+        return Err(unsupported(cmd));
+    }
+
+    let operand2 = evaluate(&PolMod(&n), cmd.operands[1].as_str(), &cmd.scope)?;
+
+    if op == Operation::DivMod || op == Operation::Mod {
         if operand2 == ZPol::zero() {
-            return Err(ParseError::ZeroDenom);
+            return Err(EvalError::ZeroDivisor { dividend: operand1.simple_display() });
         }
-        let duo = p.divmodpol(&operand1, &operand2);
+
+        let duo = match n.divmodpol(&operand1, &operand2) {
+            Ok(duo) => duo,
+            Err(gcd) => return Err(EvalError::NotInvertible {
+                element: format!("The leading coefficient {}", operand2.principal_coefficient()),
+                modulus: n.to_integer().simple_display(),
+                gcd: gcd.simple_display(),
+            }),
+        };
         let quo = duo.first; let rem= duo.second;
 
-        Ok(RawResult {
-            oper1: operand1.simple_display(),
-            oper2: operand2.simple_display(),
-            main: quo.simple_display(),
-            sub1: Some(rem.simple_display()),
-            sub2: None,
-        })
+        if op == Operation::Mod {
+            return Ok(RawResult::new(vec![Value::of(&operand1), Value::of(&operand2)], Value::of(&rem), Vec::new()))
+        }
+
+        Ok(RawResult::new(vec![Value::of(&operand1), Value::of(&operand2)], Value::of(&quo), vec![Value::of(&rem)]))
     }
     else {
         let res = match op {
-            Operation::Add => p.addpol(&operand1, &operand2),
-            Operation::Sub => p.subpol(&operand1, &operand2),
-            Operation::Mul => p.mulpol(&operand1, &operand2),
-            Operation::Mod => p.divmodpol(&operand1, &operand2).second,
-            _ => return Err(ParseError::Op),
+            Operation::Add => n.addpol(&operand1, &operand2),
+            Operation::Sub => n.subpol(&operand1, &operand2),
+            Operation::Mul => n.mulpol(&operand1, &operand2),
+            _ => return Err(unsupported(cmd)),
         };
 
-        Ok(RawResult {
-            oper1: operand1.simple_display(),
-            oper2: operand2.simple_display(),
-            main: res.simple_display(),
-            sub1: None,
-            sub2: None
-        })
+        Ok(RawResult::new(vec![Value::of(&operand1), Value::of(&operand2)], Value::of(&res), Vec::new()))
+    }
+}
+
+// the field of [GF(p^n)], or [GF(p^n, f)] for a given modulus f. The session computes it
+// once per switch to the algebra, see run_statement, as the search for a modulus is slow.
+pub(super) fn galois_field(p_int: &BigInt, n: usize, modulus: Option<&str>) -> Result<GaloisField, EvalError> {
+    let Some(p) = Prime::try_from_integer(&Integer::from_bigint(p_int.clone())) else {
+        return Err(ParseError::NotPrime.into());
+    };
+
+    // a given modulus is tested for irreducibility, which costs as much as the search.
+    let max_degree = GaloisField::max_degree(&p);
+    if n > max_degree {
+        return Err(EvalError::TooLarge {
+            what: format!("The degree {n} of GF({}^{n})", p.to_integer().simple_display()),
+            limit: format!("{max_degree} for p = {}", p.to_integer().simple_display()),
+        });
+    }
+
+    let Some(str_modulus) = modulus else {
+        return Ok(GaloisField::with_degree(&p, n));
+    };
+    let raw_modulus = ZPol::parse(str_modulus)?;
+    let Some(field) = GaloisField::try_new(&p, &raw_modulus) else {
+        return Err(ParseError::NotIrreducible.into());
+    };
+    if field.degree() != n {
+        return Err(ParseError::NotIrreducible.into());
     }
-}
\ No newline at end of file
+
+    Ok(field)
+}
+
+pub(super) fn execute_gf(p_int: &BigInt, n: usize, modulus: Option<&str>, cmd: &RawCommand)
+-> Result<RawResult, EvalError> {
+    let field = match &cmd.field {
+        Some(field) => field.clone(),
+        None => galois_field(p_int, n, modulus)?,
+    };
+
+    let op = cmd.op;
+    let operand1 = evaluate(&field, cmd.operands[0].as_str(), &cmd.scope)?;
+    let context = Some(field.modulus.simple_display());
+
+    if op == Operation::Eval {
+        return Ok(value_result(Value::of(&operand1), context));
+    }
+
+    if op == Operation::Inv {
+        let Some(inv) = field.try_inv(&operand1) else {
+            return Err(EvalError::ZeroDivisor { dividend: "1".to_string() });
+        };
+
+        return Ok(RawResult { context, ..RawResult::new(vec![Value::of(&operand1)], Value::of(&inv), Vec::new()) });
+    }
+
+    if !op.accepts(2) {
+        return Err(unsupported(cmd));
+    }
+
+    let operand2 = evaluate(&field, cmd.operands[1].as_str(), &cmd.scope)?;
+
+    let res = match op {
+        Operation::Add => field.add(&operand1, &operand2),
+        Operation::Sub => field.subtract(&operand1, &operand2),
+        Operation::Mul => field.multiply(&operand1, &operand2),
+        Operation::Div => {
+            let Some(res) = field.divide(&operand1, &operand2) else {
+                return Err(EvalError::ZeroDivisor { dividend: operand1.simple_display() });
+            };
+            res
+        }
+        _ => return Err(unsupported(cmd)),
+    };
+
+    let opers = vec![Value::of(&operand1), Value::of(&operand2)];
+    Ok(RawResult { context, ..RawResult::new(opers, Value::of(&res), Vec::new()) })
+}
+
+// in the order of RawResult::table.
+fn trace_row<T>(step: &EuclidStep<T>) -> Vec<Value>
+where T: SimpleDisplay + JsonDisplay + LatexDisplay {
+    vec![
+        Value::of(&step.dividend), Value::of(&step.quotient), Value::of(&step.divisor),
+        Value::of(&step.remainder), Value::of(&step.s), Value::of(&step.t),
+    ]
+}
+
+// the result of a bare expression is just its value.
+fn value_result(value: Value, context: Option<String>) -> RawResult {
+    RawResult { context, ..RawResult::new(vec![value.clone()], value, Vec::new()) }
+}
+
+// m is a composite factor of n that Pollard's rho could not split, see MAX_RHO_STEPS.
+fn unsplit_error(m: &Integer, n: &str) -> EvalError {
+    EvalError::TooLarge {
+        what: format!("The composite factor {} of {n}", m.simple_display()),
+        limit: format!("2^{} steps of Pollard's rho", MAX_RHO_STEPS.trailing_zeros()),
+    }
+}
+
+fn unsupported(cmd: &RawCommand) -> EvalError {
+    EvalError::Unsupported { op: cmd.op.to_string(), alg: cmd.alg.to_string() }
+}
@@ -2,6 +2,8 @@ use regex::Regex;
 use super::datatype::*;
 use super::execution;
 use super::display;
+use super::error::EvalError;
+use super::options::Format;
 
 use crate::customio::*;
 use crate::arithmetic::*;
@@ -10,8 +12,14 @@ pub(super) fn as_algebra(raw_input: &str) -> Result<Algebra, ParseError> {
     let input_string = raw_input.to_ascii_uppercase();
     let input = input_string.as_str();
     
-    let re_f = Regex::new(r"^F\(([0-9]+)\)$").unwrap();
-    let re_fpol = Regex::new(r"^FPOL\(([0-9]+)\)$").unwrap();
+    // the moduli are matched on the raw input, so that errors quote them as typed.
+    let re_f = Regex::new(r"^(?i:F)\((.+)\)$").unwrap();
+    let re_fpol = Regex::new(r"^(?i:FPOL)\((.+)\)$").unwrap();
+    let re_zmod = Regex::new(r"^(?i:ZMOD)\((.+)\)$").unwrap();
+    let re_zmodpol = Regex::new(r"^(?i:ZMOD)\((.+)\)(?i:POL)$").unwrap();
+    // GF(p^n) or GF(p^n,f). Matched on the raw input, since f is case-sensitive.
+    // p is the shortest expression followed by ^n, if any, eg. 2^61-1 in GF(2^61-1^2).
+    let re_gf = Regex::new(r"^(?i:GF)\(([^,]+?)(?:\^([0-9]+))?(?:,(.+))?\)$").unwrap();
 
     // simple situations
     if input == "Z" {
@@ -27,29 +35,54 @@ pub(super) fn as_algebra(raw_input: &str) -> Result<Algebra, ParseError> {
         return Ok(Algebra::Zi);
     }
 
-    // F(p) case
-    if re_f.is_match(input){
-        let cap = re_f.captures(input).unwrap();
-        let (_, [p_str]) = cap.extract();
-        
-        let p: int = p_str.parse().unwrap();
-
-        return Ok(Algebra::F(p));
+    // F(p), FPol(p), Zmod(n) and Zmod(n)Pol. The modulus is an integer expression
+    // of any size, eg. F(2^127-1).
+    if let Some(cap) = re_f.captures(raw_input) {
+        return Ok(Algebra::F(as_modulus(&cap[1])?));
+    }
+    if let Some(cap) = re_fpol.captures(raw_input) {
+        return Ok(Algebra::FPol(as_modulus(&cap[1])?));
+    }
+    if let Some(cap) = re_zmodpol.captures(raw_input) {
+        return Ok(Algebra::ZmodPol(as_modulus(&cap[1])?));
+    }
+    if let Some(cap) = re_zmod.captures(raw_input) {
+        return Ok(Algebra::Zmod(as_modulus(&cap[1])?));
     }
 
-    // FPol(p) case 
-    if re_fpol.is_match(input){
-        let cap = re_fpol.captures(input).unwrap();
-        let (_, [p_str]) = cap.extract();
-        
-        let p: int = p_str.parse().unwrap();
+    // GF(p^n) case. GF(p) is short for GF(p^1).
+    if let Some(cap) = re_gf.captures(raw_input) {
+        // n is made of digits, so a failed parse means overflow.
+        let p = as_modulus(&cap[1])?;
+        let n: usize = match cap.get(2) {
+            None => 1,
+            Some(n_str) => {
+                let Ok(n) = n_str.as_str().parse::<usize>() else {
+                    return Err(ParseError::Overflow);
+                };
+                n
+            }
+        };
+        if n == 0 {
+            return Err(ParseError::FieldDegree);
+        }
+        if n > MAX_DEGREE {
+            return Err(ParseError::Degree);
+        }
+        let modulus = cap.get(3).map(|m| m.as_str().to_string());
 
-        return Ok(Algebra::FPol(p));
+        return Ok(Algebra::GF(p, n, modulus));
     }
 
     Err(ParseError::Algebra)
 }
 
+// the modulus of an algebra, evaluated in Z. Whether it is prime, or at least 2,
+// is checked by the executors.
+fn as_modulus(input: &str) -> Result<BigInt, ParseError> {
+    Ok(Integer::parse(input)?.number)
+}
+
 pub(super) fn as_operation(input: &str) -> Result<Operation, ParseError> {
     match input.to_lowercase().as_str() {
         "add" => Ok(Operation::Add),
@@ -59,60 +92,452 @@ pub(super) fn as_operation(input: &str) -> Result<Operation, ParseError> {
         "mod" => Ok(Operation::Mod),
         "divmod" => Ok(Operation::DivMod),
         "bezout" => Ok(Operation::Bezout),
+        // "bezout --steps", its whitespace stripped like the rest of the command.
+        "trace" | "bezout--steps" => Ok(Operation::Trace),
+        "gcd" => Ok(Operation::Gcd),
+        "lcm" => Ok(Operation::Lcm),
+        "pow" => Ok(Operation::Pow),
+        "order" => Ok(Operation::Order),
+        "primroot" => Ok(Operation::PrimRoot),
+        "isprimroot" => Ok(Operation::IsPrimRoot),
+        "dlog" => Ok(Operation::Dlog),
+        "sqrt" => Ok(Operation::Sqrt),
+        "legendre" => Ok(Operation::Legendre),
+        "jacobi" => Ok(Operation::Jacobi),
+        "isunit" => Ok(Operation::IsUnit),
+        "iszerodivisor" => Ok(Operation::IsZeroDivisor),
+        "inv" => Ok(Operation::Inv),
+        "factor" => Ok(Operation::Factor),
+        "isprime" => Ok(Operation::IsPrime),
+        "nextprime" => Ok(Operation::NextPrime),
+        "totient" => Ok(Operation::Totient),
+        "sigma" => Ok(Operation::Sigma),
+        "numdivisors" => Ok(Operation::NumDivisors),
+        "mobius" => Ok(Operation::Mobius),
+        "carmichael" => Ok(Operation::Carmichael),
+        "divisors" => Ok(Operation::Divisors),
+        "crt" => Ok(Operation::Crt),
+        "solve" => Ok(Operation::Solve),
+        "congruence" => Ok(Operation::Congruence),
+        "contfrac" => Ok(Operation::Contfrac),
+        "pell" => Ok(Operation::Pell),
         _ => Err(ParseError::Op),
     }
 }
 
+// names that cannot be bound: those of the algebras (x, i), ans and the commands,
+// besides the operations.
+const RESERVED_NAMES: [&str; 7] = ["x", "i", "ans", "let", "vars", "clear", "exit"];
+
+fn as_name(input: &str) -> Result<String, ParseError> {
+    let re_name = Regex::new(r"^[A-Za-z][A-Za-z0-9_]*$").unwrap();
+    if !re_name.is_match(input) || RESERVED_NAMES.contains(&input.to_lowercase().as_str())
+        || as_operation(input).is_ok() {
+        return Err(ParseError::Name(input.to_string()));
+    }
+
+    Ok(input.to_string())
+}
+
+// session commands first, then commands, possibly bound with let.
+pub(super) fn parse_statement(raw_input: &str) -> Result<Statement, ParseError> {
+    let input = raw_input.trim();
+    let re_let = Regex::new(r"^(?i:let)\s+([^=\s]*)\s*=(.*)$").unwrap();
+    let re_clear = Regex::new(r"^(?i:clear)(?:\s+(\S+))?$").unwrap();
+    let re_format = Regex::new(r"^:(?i:format)(?:\s+(\S+)(?:\s+(.*))?)?$").unwrap();
+
+    if input.eq_ignore_ascii_case("vars") {
+        return Ok(Statement::Vars);
+    }
+
+    if let Some(caps) = re_clear.captures(input) {
+        return match caps.get(1) {
+            None => Ok(Statement::Clear(None)),
+            Some(name) => Ok(Statement::Clear(Some(as_name(name.as_str())?))),
+        };
+    }
+
+    if let Some(caps) = re_format.captures(input) {
+        let Some(name) = caps.get(1) else {
+            return Ok(Statement::Format(None));
+        };
+        let Some(format) = Format::from_name(name.as_str()) else {
+            return Err(ParseError::OutputFormat(name.as_str().to_string()));
+        };
+
+        return match caps.get(2) {
+            None => Ok(Statement::Format(Some(format))),
+            Some(rest) => match parse_statement(rest.as_str())? {
+                Statement::Format(_) | Statement::Formatted(..) => Err(ParseError::Format),
+                statement => Ok(Statement::Formatted(format, Box::new(statement))),
+            },
+        };
+    }
+
+    if let Some(caps) = re_let.captures(input) {
+        let (_, [raw_name, raw_cmd]) = caps.extract();
+        let name = as_name(raw_name)?;
+        let cmd = raw_parse(raw_cmd)?;
+        if cmd.op.is_query() {
+            return Err(ParseError::Query);
+        }
+
+        return Ok(Statement::Let(name, cmd));
+    }
+
+    Ok(Statement::Command(raw_parse(input)?))
+}
+
+// either "[ALG] op {A} {B} ..." or "[ALG] expression". Braces tell them apart.
 pub(super) fn raw_parse(raw_input: &str) -> Result<RawCommand, ParseError>{
     if !raw_input.is_ascii() {return Err(ParseError::NotAscii)};
-    let trimmed_string: String = raw_input.split_ascii_whitespace().collect();
-    let input = trimmed_string.as_str();
 
-    let re = Regex::new(r"^\[(.*)\](.*)\{(.*)\}\{(.*)\}$").unwrap();
-    let Some(caps) = re.captures(input) else {
+    let re = Regex::new(r"^\s*\[([^\[\]]*)\](.*)$").unwrap();
+    let re_op = Regex::new(r"^([^{}]*)((?:\{[^{}]*\})+)$").unwrap();
+    let re_operand = Regex::new(r"\{([^{}]*)\}").unwrap();
+    let re_call = Regex::new(r"^([A-Za-z]+)(?:\s+[\w($]|\(|$)").unwrap();
+    let Some(caps) = re.captures(raw_input) else {
         return Err(ParseError::Format);
     };
 
-    let (_, [raw_alg, raw_op, raw_arg1, raw_arg2]) = caps.extract();
+    let (_, [raw_alg, raw_rest]) = caps.extract();
+
+    let trimmed_alg: String = raw_alg.split_ascii_whitespace().collect();
+    let alg = as_algebra(&trimmed_alg)?;
 
-    let alg = as_algebra(raw_alg)?;
+    if !raw_rest.contains(['{', '}']) {
+        if raw_rest.trim().is_empty() {
+            return Err(ParseError::Format);
+        }
+
+        // an operation without operands, rather than an expression.
+        if let Ok(op) = as_operation(raw_rest.trim()) && op.accepts(0) {
+            return Ok(RawCommand{ alg, op, operands: Vec::new(), scope: Scope::new(), field: None });
+        }
+        // an operation with its operands outside braces, like gcd 4 6 or sqrt(2), or none.
+        if let Some(caps) = re_call.captures(raw_rest.trim()) && as_operation(&caps[1]).is_ok() {
+            return Err(ParseError::Braces(caps[1].to_lowercase()));
+        }
+
+        return Ok(RawCommand{
+            alg,
+            op: Operation::Eval,
+            operands: vec![raw_rest.trim().to_string()],
+            scope: Scope::new(),
+            field: None,
+        });
+    }
+
+    let trimmed_rest: String = raw_rest.split_ascii_whitespace().collect();
+    let Some(caps) = re_op.captures(&trimmed_rest) else {
+        return Err(ParseError::Format);
+    };
+
+    let (_, [raw_op, raw_args]) = caps.extract();
 
     let op = as_operation(raw_op)?;
 
-    let operand1 = String::from(raw_arg1);
+    let operands: Vec<String> = re_operand.captures_iter(raw_args)
+        .map(|cap| String::from(&cap[1]))
+        .collect();
 
-    let operand2 = String::from(raw_arg2);
+    if !op.accepts(operands.len()) {
+        return Err(ParseError::Arity);
+    }
 
     Ok(RawCommand{
-        alg: alg,
-        op: op,
-        operand1: operand1,
-        operand2: operand2,
-        
+        alg,
+        op,
+        operands,
+        scope: Scope::new(),
+        field: None,
     })
 }
 
-pub(super) fn execute(cmd: &RawCommand) -> Result<RawResult, ParseError> {
-    match cmd.alg {
-        Algebra::Z => execution::execute_ring::<Integer>(cmd),
-        Algebra::Q => execution::execute_field::<Rational>(cmd),
-        Algebra::QPol => execution::execute_ring::<Polynomial<Rational>>(cmd),
-        Algebra::F(_) => execution::execute_f(cmd),
-        Algebra::FPol(_) => execution::execute_fpol(cmd),
-        Algebra::Zi => execution::execute_ring::<GaussInteger>(cmd)
+pub(super) fn execute(cmd: &RawCommand) -> Result<RawResult, EvalError> {
+    // only F(p) has operations without operands; the other executors read one up front.
+    if cmd.operands.is_empty() && !matches!(cmd.alg, Algebra::F(_)) {
+        return Err(EvalError::Unsupported { op: cmd.op.to_string(), alg: cmd.alg.to_string() });
     }
+    // and only Z and F(p) have quadratic residues, whose arity differs between the two.
+    let is_residue = matches!(cmd.op, Operation::Sqrt | Operation::Legendre | Operation::Jacobi);
+    if is_residue && !matches!(cmd.alg, Algebra::Z | Algebra::F(_)) {
+        return Err(EvalError::Unsupported { op: cmd.op.to_string(), alg: cmd.alg.to_string() });
+    }
+    if cmd.op.is_arithmetic_function() && cmd.alg != Algebra::Z {
+        return Err(EvalError::Unsupported { op: cmd.op.to_string(), alg: cmd.alg.to_string() });
+    }
+    let is_contfrac_of = |alg: &Algebra| matches!(alg, Algebra::Z | Algebra::Q);
+    if cmd.op == Operation::Contfrac && !is_contfrac_of(&cmd.alg) || cmd.op == Operation::Pell && cmd.alg != Algebra::Z {
+        return Err(EvalError::Unsupported { op: cmd.op.to_string(), alg: cmd.alg.to_string() });
+    }
+
+    // the arithmetic layer reports zero divisors and the like through Option, so a panic
+    // here is a bug. Should one slip through, it must not take the REPL down with it: it is
+    // reported as EvalError::Internal, once the panic hook has printed its message.
+    let res = std::panic::catch_unwind(|| match &cmd.alg {
+        Algebra::Z => execution::execute_z(cmd),
+        Algebra::Q => execution::execute_q(cmd),
+        Algebra::QPol => execution::execute_qpol(cmd),
+        Algebra::F(p) => execution::execute_f(p, cmd),
+        Algebra::FPol(p) => execution::execute_fpol(p, cmd),
+        Algebra::Zi => execution::execute_zi(cmd),
+        Algebra::Zmod(n) => execution::execute_zmod(n, cmd),
+        Algebra::ZmodPol(n) => execution::execute_zmodpol(n, cmd),
+        Algebra::GF(p, n, modulus) => execution::execute_gf(p, *n, modulus.as_deref(), cmd),
+    });
+
+    res.unwrap_or_else(|payload| {
+        let msg = if let Some(str) = payload.downcast_ref::<&str>() {
+            str.to_string()
+        } else if let Some(string) = payload.downcast_ref::<String>() {
+            string.clone()
+        } else {
+            "unknown panic".to_string()
+        };
+
+        Err(EvalError::Internal { msg })
+    })
 }
 
 pub(super) fn display(cmd: RawCommand, res: RawResult) -> String {
-    match cmd.alg {
+    match cmd.alg.clone() {
         Algebra::Z => display::display_ring(cmd, res),
-        Algebra::Q => display::display_field(cmd, res),
+        Algebra::Q => display::display_q(cmd, res),
         Algebra::QPol => display::display_ring(cmd, res),
-        Algebra::F(_) => display::display_f(cmd, res),
-        Algebra::FPol(_) => display::display_fpol(cmd, res),
-        Algebra::Zi => display::display_ring(cmd, res)
+        Algebra::F(p) => display::display_f(&p, cmd, res),
+        Algebra::FPol(p) => display::display_fpol(&p, cmd, res),
+        Algebra::Zi => display::display_ring(cmd, res),
+        Algebra::Zmod(n) => display::display_zmod(&n, cmd, res),
+        Algebra::ZmodPol(n) => display::display_fpol(&n, cmd, res),
+        Algebra::GF(..) => display::display_gf(cmd, res),
     }
 }
 
+pub(super) fn display_latex(cmd: RawCommand, res: RawResult) -> String {
+    display::display_latex(cmd, res)
+}
+
+pub(super) fn display_json(n: usize, name: Option<String>, cmd: RawCommand, res: RawResult) -> Json {
+    display::display_json(n, name, cmd, res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(line: &str) -> Result<RawResult, EvalError> {
+        execute(&raw_parse(line)?)
+    }
+
+    #[test]
+    fn integers_have_arbitrary_precision() {
+        let res = run("[Z] mul {10000000000}{10000000000}").unwrap();
+        assert_eq!(res.main.text, "100000000000000000000");
+
+        // 2^127 - 1 and 2^89 - 1 are coprime Mersenne primes.
+        let res = run("[Z] gcd {170141183460469231731687303715884105727}{618970019642690137449562111}").unwrap();
+        assert_eq!(res.main.text, "1");
+
+        // 2^200 = 2^73 mod 2^127 - 1.
+        let res = run("[F(2^127-1)] mul {2^100}{2^100}").unwrap();
+        assert_eq!(res.main.text, "9444732965739290427392");
+    }
+
+    #[test]
+    fn oversized_input_is_an_error() {
+        assert_eq!(as_algebra("GF(2^99999999999999999999)"), Err(ParseError::Overflow));
+        assert_eq!(run("[Z] mul {2^(10^30)}{2}").unwrap_err(), EvalError::Parse(ParseError::Size));
+        assert_eq!(run("[Zi] add {(1+i)^(10^7)}{1}").unwrap_err(), EvalError::Parse(ParseError::Size));
+        assert_eq!(run("[Q] (2/3)^(10^7)").unwrap_err(), EvalError::Parse(ParseError::Size));
+    }
+
+    #[test]
+    fn bad_input_is_an_error() {
+        assert_eq!(run("[Z] mod {7}{0}").unwrap_err(), EvalError::ZeroDivisor { dividend: "7".to_string() });
+        assert_eq!(run("[QPol] divmod {x}{0}").unwrap_err(), EvalError::ZeroDivisor { dividend: "x".to_string() });
+        assert_eq!(run("[Q] div {1/2}{0}").unwrap_err(), EvalError::ZeroDivisor { dividend: "1/2".to_string() });
+        assert_eq!(run("[F(7)] div {3}{14}").unwrap_err(), EvalError::ZeroDivisor { dividend: "3".to_string() });
+        assert_eq!(run("[F(7)] bezout {1}{2}").unwrap_err(),
+            EvalError::Unsupported { op: "bezout".to_string(), alg: "F(7)".to_string() });
+        assert_eq!(run("[Z] 1/0").unwrap_err(), EvalError::Parse(ParseError::ZeroDenom));
+
+        // a 30-digit modulus, which is no prime.
+        assert_eq!(run("[F(100000000000000000000000000000)] add {1}{2}").unwrap_err(),
+            EvalError::Parse(ParseError::NotPrime));
+    }
+
+    #[test]
+    fn zmodpol_divides_by_unit_leading_coefficients() {
+        let res = run("[Zmod(6)Pol] divmod {5x^2 + 1}{5x + 1}").unwrap();
+        assert_eq!(res.main.text, "x + 1");
+        assert_eq!(res.subs[0].text, "0");
+
+        assert_eq!(run("[Zmod(6)Pol] mod {x^2}{2x + 1}").unwrap_err(), EvalError::NotInvertible {
+            element: "The leading coefficient 2".to_string(),
+            modulus: "6".to_string(),
+            gcd: "2".to_string(),
+        });
+    }
+
+    #[test]
+    fn gf_characteristic_is_an_expression() {
+        let p = BigInt::from(2305843009213693951i64);
+        assert_eq!(as_algebra("GF(2^61-1^2)"), Ok(Algebra::GF(p.clone(), 2, None)));
+        assert_eq!(as_algebra("GF((2^61-1)^2)"), Ok(Algebra::GF(p.clone(), 2, None)));
+        assert_eq!(as_algebra("GF(2^61-1)"), Ok(Algebra::GF(p, 1, None)));
+        assert_eq!(as_algebra("GF(2^8,x^8+x^4+x^3+x+1)"),
+            Ok(Algebra::GF(BigInt::from(2), 8, Some("x^8+x^4+x^3+x+1".to_string()))));
+        assert_eq!(as_algebra("GF(y^2)"), Err(ParseError::Unknown("y".to_string())));
 
+        // -1 is no square mod p = 3 mod 4, so x^2 = -1.
+        let res = run("[GF(2^61-1^2)] mul {x}{x}").unwrap();
+        assert_eq!(res.context.unwrap(), "x^2 + 1");
+        assert_eq!(res.main.text, "2305843009213693950");
+    }
+
+    #[test]
+    fn gf_degree_is_capped() {
+        assert_eq!(run("[GF(2^1000)] mul {x}{x}").unwrap_err(), EvalError::TooLarge {
+            what: "The degree 1000 of GF(2^1000)".to_string(),
+            limit: "128 for p = 2".to_string(),
+        });
+        // a given modulus has to be tested for irreducibility, which costs as much.
+        assert!(matches!(run("[GF(2^500, x^500 + x + 1)] mul {x}{x}"), Err(EvalError::TooLarge { .. })));
+
+        let res = run("[GF(1000000007^5)] mul {x^4}{x}").unwrap();
+        assert!(res.context.unwrap().starts_with("x^5 + "));
+    }
+
+    #[test]
+    fn large_primes_are_probable() {
+        let shown = |line: &str| {
+            let cmd = raw_parse(line).unwrap();
+            let res = execute(&cmd).unwrap();
+            display(cmd, res)
+        };
+        assert_eq!(shown("[Z] isprime {2^61 - 1}"), "2305843009213693951 is prime.");
+        assert_eq!(shown("[Z] isprime {2^127 - 1}"), "170141183460469231731687303715884105727 is a probable prime.");
+        assert_eq!(shown("[Z] isprime {2^127 + 1}"), "170141183460469231731687303715884105729 is not prime.");
+    }
 
+    #[test]
+    fn latex_signs() {
+        let latex = |line: &str| {
+            let cmd = raw_parse(line).unwrap();
+            let res = execute(&cmd).unwrap();
+            display_latex(cmd, res)
+        };
+        // negative terms fold into the sign before them, negative factors are wrapped.
+        assert_eq!(latex("[Q] add {1/2}{-3/4}"), "\\frac{1}{2} - \\frac{3}{4} = -\\frac{1}{4} \\text{ in } \\mathbb{Q}");
+        assert_eq!(latex("[Z] mul {-2}{-3}"), "-2 \\cdot \\left(-3\\right) = 6 \\text{ in } \\mathbb{Z}");
+        assert_eq!(latex("[Zi] sub {1}{-i}"), "1 + i = 1 + i \\text{ in } \\mathbb{Z}[i]");
+        assert_eq!(latex("[QPol] sub {x}{-x^2 + 1}"),
+            "x - \\left(-x^{2} + 1\\right) = x^{2} + x - 1 \\text{ in } \\mathbb{Q}[x]");
+        assert_eq!(latex("[Z] solve {3}{-5}{1}"), "3 X - 5 Y = 1 \\iff X = 2 + 5 T,\\ Y = 1 + 3 T,\\ T \\in \\mathbb{Z}");
+        assert_eq!(latex("[Q] (-2)^3"), "-8");
+    }
+
+    #[test]
+    fn crt_moduli_must_be_nonzero() {
+        for line in ["[Z] crt {2}{3} {1}{0}", "[FPol(5)] crt {1}{x} {x}{5x}"] {
+            assert!(matches!(run(line), Err(EvalError::Domain { .. })), "{line}");
+        }
+
+        // F(p)[x] goes through the same algorithms as the other Euclidean rings.
+        let res = run("[FPol(5)] crt {1}{x} {x}{x^2 + 1}").unwrap();
+        assert_eq!(res.main.text, "x^2 + x + 1");
+        assert_eq!(res.subs[0].text, "x^3 + x");
+        let res = run("[FPol(5)] trace {x^2 + 1}{x + 1}").unwrap();
+        assert_eq!(res.main.text, "1");
+        assert_eq!(res.table.len(), 2);
+    }
+
+    #[test]
+    fn trace_tables() {
+        let cmd = raw_parse("[Z] bezout --steps {240}{46}").unwrap();
+        assert_eq!(cmd.op, Operation::Trace);
+        let res = execute(&cmd).unwrap();
+        let rows: Vec<Vec<&str>> = res.table.iter().map(|row| row.iter().map(|x| x.text.as_str()).collect()).collect();
+        assert_eq!(rows, vec![
+            vec!["240", "5", "46", "10", "1", "-5"],
+            vec!["46", "4", "10", "6", "-4", "21"],
+            vec!["10", "1", "6", "4", "5", "-26"],
+            vec!["6", "1", "4", "2", "-9", "47"],
+            vec!["4", "2", "2", "0", "23", "-120"],
+        ]);
+        // the table comes first, then the same summary as bezout.
+        let shown = display(cmd, res);
+        assert!(shown.starts_with("Euclidean algorithm, where dividend = quotient * divisor + remainder\n"));
+        assert!(shown.contains("\ndividend | quotient | divisor | remainder | s  | t\n240      | 5 "));
+        assert!(shown.ends_with("\n\nU * F + V * G = H, where\nU = -9,\nF = 240,\nV = 47,\nG = 46,\n[GCD] H = 2."));
+
+        let res = run("[Zi] trace {11 + 3i}{1 + 8i}").unwrap();
+        assert_eq!(res.table.len(), 3);
+        assert_eq!(res.table[2][3].text, "0");
+        assert_eq!(res.main.text, "2+i");
+        // (F, 0) has no division to show.
+        assert!(run("[Z] trace {5}{0}").unwrap().table.is_empty());
+        // unlike bezout, a trace takes exactly two operands.
+        assert_eq!(run("[Z] trace {1}{2}{3}").unwrap_err(), EvalError::Parse(ParseError::Arity));
+    }
+
+    #[test]
+    fn gcd_lcm_and_bezout_of_many_operands() {
+        let res = run("[Z] bezout {12}{18}{27}").unwrap();
+        assert_eq!(res.opers.len(), 3);
+        assert_eq!(res.main.text, "3");
+        let coeffs: Vec<Integer> = res.subs.iter().map(|u| Integer::parse(&u.text).unwrap()).collect();
+        let sum = Integer::new(12) * coeffs[0].clone() + Integer::new(18) * coeffs[1].clone() + Integer::new(27) * coeffs[2].clone();
+        assert_eq!(sum, Integer::new(3));
+
+        assert_eq!(run("[Z] gcd {-4}{6}{-10}{0}").unwrap().main.text, "2");
+        assert_eq!(run("[Z] lcm {4}{6}{10}").unwrap().main.text, "60");
+        assert_eq!(run("[Z] lcm {4}{0}{6}").unwrap().main.text, "0");
+        assert_eq!(run("[QPol] gcd {x^2 - 1}{x^2 + x}{x^3 - x}").unwrap().main.text, "x + 1");
+        assert_eq!(run("[FPol(5)] lcm {x}{x + 1}{x^2 + x}").unwrap().main.text, "x^2 + x");
+
+        // one operand is not enough, but four are.
+        assert_eq!(run("[Z] gcd {4}").unwrap_err(), EvalError::Parse(ParseError::Arity));
+        assert_eq!(run("[Zi] bezout {3 + i}{2}{1 + i}{5}").unwrap().subs.len(), 4);
+    }
+
+    #[test]
+    fn periods_are_capped() {
+        assert_eq!(run("[Z] contfrac {13}").unwrap().subs[0].text, "5");
+        assert_eq!(run("[Z] pell {61}").unwrap().main.text, "29718");
+        for line in ["[Z] contfrac {100000231}", "[Z] pell {100000231}"] {
+            assert!(matches!(run(line), Err(EvalError::TooLarge { .. })), "{line}");
+        }
+    }
+
+    #[test]
+    fn operations_need_braces() {
+        assert_eq!(raw_parse("[Z] bezout 4 6").unwrap_err(), ParseError::Braces("bezout".to_string()));
+        assert_eq!(raw_parse("[Z] Sqrt(4)").unwrap_err(), ParseError::Braces("sqrt".to_string()));
+        assert_eq!(raw_parse("[Z] gcd").unwrap_err(), ParseError::Braces("gcd".to_string()));
+        assert_eq!(parse_statement("let gcd = [Z] 2").unwrap_err(), ParseError::Name("gcd".to_string()));
+
+        // names that only start like an operation are no operations.
+        assert!(raw_parse("[Z] modulus + 1").is_ok());
+        assert!(raw_parse("[F(7)] primroot").is_ok());
+    }
+
+    #[test]
+    fn session_statements() {
+        assert!(matches!(parse_statement("VARS"), Ok(Statement::Vars)));
+        assert!(matches!(parse_statement("clear"), Ok(Statement::Clear(None))));
+        assert!(matches!(parse_statement("clear f"), Ok(Statement::Clear(Some(name))) if name == "f"));
+        assert_eq!(parse_statement("clear 2f").unwrap_err(), ParseError::Name("2f".to_string()));
+
+        let Ok(Statement::Let(name, cmd)) = parse_statement("let f = [QPol] (x + 1)^2") else {
+            panic!("a binding.");
+        };
+        assert_eq!(name, "f");
+        assert_eq!(cmd.alg, Algebra::QPol);
+        assert_eq!(cmd.operands, vec!["(x + 1)^2".to_string()]);
+
+        assert_eq!(parse_statement("let x = [Z] 1").unwrap_err(), ParseError::Name("x".to_string()));
+        assert_eq!(parse_statement("let b = [Z] isprime {7}").unwrap_err(), ParseError::Query);
+    }
+}
@@ -3,36 +3,304 @@ mod display;
 mod datatype;
 mod func;
 mod input;
+mod error;
+mod session;
+mod options;
+pub use error::EvalError;
 
-pub fn go(){
+use crate::customio;
+use std::io::{BufRead, BufReader, IsTerminal};
+use std::process::ExitCode;
+
+// exit status: 0 on success, 1 if a command of a batch failed, 2 for bad usage.
+pub fn go() -> ExitCode {
+    let options = match options::parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(msg) => {
+            eprintln!("Error: {msg}\n\n{}", options::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    if options.help {
+        println!("{}", options::USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    if !options.commands.is_empty() {
+        let commands = options.commands.clone().into_iter().map(Ok);
+        return batch(commands, "command", options);
+    }
+
+    match options.script.clone() {
+        Some(path) => match std::fs::File::open(&path) {
+            Ok(file) => batch(BufReader::new(file).lines(), "line", options),
+            Err(err) => {
+                eprintln!("Error: cannot read {path}: {err}");
+                ExitCode::from(2)
+            }
+        },
+        None if !std::io::stdin().is_terminal() => batch(std::io::stdin().lock().lines(), "line", options),
+        None => {
+            repl(options);
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+// :format changes the options for the rest of the session.
+fn repl(mut options: options::Options) {
     let mut reader = input::new_editor();
+    let mut session = session::Session::new();
 
     loop {
         let Ok(input) = reader.readline("> ") else {
-            println!("Goodbye!\n");
+            goodbye(&options);
             break;
         };
 
         let input_str = input.trim();
-        if input_str.to_ascii_lowercase() == "exit" {
-            println!("Goodbye!\n");
+        if input_str.eq_ignore_ascii_case("exit") {
+            goodbye(&options);
             break;
         }
 
-        let res_cmd = func::raw_parse(input_str);
-        let Ok(cmd) = res_cmd else {
-            res_cmd.unwrap_err().print();
-            continue;
+        match run_line(&mut session, &mut options, input_str) {
+            Ok(outcome) => print!("{}", render(outcome, &options)),
+            Err(err) if options.format == options::Format::Json => println!("{}", error_json(&err, None)),
+            Err(err) if compact(&options) => println!("Error: {}", err.message()),
+            Err(err) => err.print(),
+        }
+    }
+}
+
+fn goodbye(options: &options::Options) {
+    if !options.quiet {
+        print!("{}", render(Outcome::Message("Goodbye!".to_string()), options));
+    }
+}
+
+// lines are run as in the REPL. Blank lines and comments starting with # are skipped.
+// Errors go to stderr with their number, and stop the batch unless keep_going.
+fn batch(lines: impl Iterator<Item = std::io::Result<String>>, unit: &str, mut options: options::Options)
+-> ExitCode {
+    let mut session = session::Session::new();
+    let mut status = ExitCode::SUCCESS;
+
+    for (i, line) in lines.enumerate() {
+        let line_no = i + 1;
+        let Ok(line) = line else {
+            eprintln!("Error in {unit} {line_no}: the input is not valid UTF-8.");
+            return ExitCode::FAILURE;
         };
 
-        let res_res = func::execute(&cmd);
-        let Ok(res) = res_res else {
-            res_res.unwrap_err().print();
+        let input_str = line.trim();
+        if input_str.is_empty() || input_str.starts_with('#') {
             continue;
+        }
+        if input_str.eq_ignore_ascii_case("exit") {
+            break;
+        }
+
+        match run_line(&mut session, &mut options, input_str) {
+            Ok(outcome) => print!("{}", render(outcome, &options)),
+            Err(err) => {
+                if options.format == options::Format::Json {
+                    eprintln!("{}", error_json(&err, Some((unit, line_no))));
+                } else {
+                    eprintln!("Error in {unit} {line_no}: {}", err.message());
+                }
+                if !options.keep_going {
+                    return ExitCode::FAILURE;
+                }
+                status = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    status
+}
+
+// what a line of input produced: a message from a session command, or result $n.
+// Formatted is an outcome to show in another format than the session's.
+enum Outcome {
+    Message(String),
+    Formatted(options::Format, Box<Outcome>),
+    Result { n: usize, name: Option<String>, cmd: Box<datatype::RawCommand>, res: Box<datatype::RawResult> },
+}
+
+// runs a line of input against the session.
+fn run_line(session: &mut session::Session, options: &mut options::Options, input_str: &str)
+-> Result<Outcome, EvalError> {
+    let statement = func::parse_statement(input_str)?;
+    run_statement(session, options, statement)
+}
+
+fn run_statement(session: &mut session::Session, options: &mut options::Options, statement: datatype::Statement)
+-> Result<Outcome, EvalError> {
+    let (name, mut cmd) = match statement {
+        datatype::Statement::Vars => return Ok(Outcome::Message(session.list())),
+        datatype::Statement::Format(None) => {
+            return Ok(Outcome::Message(format!("The output format is {}.", options.format)));
+        }
+        datatype::Statement::Format(Some(format)) => {
+            options.format = format;
+            return Ok(Outcome::Message(format!("The output format is now {format}.")));
+        }
+        datatype::Statement::Formatted(format, statement) => {
+            let outcome = run_statement(session, options, *statement)?;
+            return Ok(Outcome::Formatted(format, Box::new(outcome)));
+        }
+        datatype::Statement::Clear(None) => {
+            session.clear();
+            return Ok(Outcome::Message("Cleared all variables.".to_string()));
+        }
+        datatype::Statement::Clear(Some(name)) => {
+            if !session.unbind(&name) {
+                return Err(customio::ParseError::Unknown(name).into());
+            }
+            return Ok(Outcome::Message(format!("Cleared {name}.")));
+        }
+        datatype::Statement::Command(cmd) => (None, cmd),
+        datatype::Statement::Let(name, cmd) => (Some(name), cmd),
+    };
+
+    cmd.scope = session.scope(&cmd.alg);
+    if let datatype::Algebra::GF(p, n, modulus) = &cmd.alg {
+        let field = match session.field(&cmd.alg) {
+            Some(field) => field,
+            None => execution::galois_field(p, *n, modulus.as_deref())?,
         };
+        session.keep_field(&cmd.alg, &field);
+        cmd.field = Some(field);
+    }
+
+    let res = func::execute(&cmd)?;
+
+    let n = session.record(&cmd.alg, cmd.op, &res);
+    if let Some(ref name) = name {
+        session.bind(name, &cmd.alg, &res.main.text);
+    }
+
+    Ok(Outcome::Result { n, name, cmd: Box::new(cmd), res: Box::new(res) })
+}
+
+// the text to print for an outcome, with its trailing newlines.
+fn render(outcome: Outcome, options: &options::Options) -> String {
+    let (n, name, cmd, res) = match outcome {
+        Outcome::Formatted(format, outcome) => {
+            return render(*outcome, &options::Options { format, ..options.clone() });
+        }
+        Outcome::Message(msg) if options.format == options::Format::Json => {
+            return format!("{}\n", customio::Json::object(vec![("message", customio::Json::Str(msg))]).render());
+        }
+        Outcome::Message(msg) if compact(options) => return format!("{msg}\n"),
+        Outcome::Message(msg) => return format!("{msg}\n\n"),
+        Outcome::Result { n, name, cmd, res } => (n, name, *cmd, *res),
+    };
+
+    let output_str = match (options.format, name) {
+        // one object per line, labels included, whether quiet or not.
+        (options::Format::Json, name) => return format!("{}\n", func::display_json(n, name, cmd, res).render()),
+        (options::Format::Plain, _) => {
+            let values: Vec<String> = std::iter::once(res.main).chain(res.subs).map(|x| x.text).collect();
+            return format!("{}\n", values.join("\n"));
+        }
+        (options::Format::Pretty, Some(name)) => format!("{name} = {}", res.main),
+        (options::Format::Latex, Some(name)) => format!("{name} = {}", res.main.latex),
+        (options::Format::Latex, None) => func::display_latex(cmd, res),
+        (options::Format::Pretty, None) => func::display(cmd, res),
+    };
+
+    if options.quiet {
+        format!("{output_str}\n")
+    } else if options.format == options::Format::Latex {
+        // the label as text, so that the output can still be pasted in math mode.
+        format!("\\text{{Result \\${n}: }} {output_str}\n\n")
+    } else {
+        format!("Result ${n}: {output_str}\n\n")
+    }
+}
+
+// plain output, like json, is read by other programs: one line after another, without
+// the blank lines that separate outputs in the REPL.
+fn compact(options: &options::Options) -> bool {
+    options.quiet || options.format == options::Format::Plain
+}
+
+// errors in the json format: {"error": message}, with the line or command number in a batch.
+fn error_json(err: &EvalError, at: Option<(&str, usize)>) -> String {
+    let mut fields = vec![("error", customio::Json::Str(err.message()))];
+    if let Some((unit, number)) = at {
+        fields.push((unit, customio::Json::Number(number)));
+    }
 
-        let output_str = func::display(cmd, res);
+    customio::Json::object(fields).render()
+}
 
-        println!("Result: {}\n", output_str);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(input: &[&str]) -> impl Iterator<Item = std::io::Result<String>> {
+        input.iter().map(|line| Ok(line.to_string())).collect::<Vec<_>>().into_iter()
+    }
+
+    #[test]
+    fn batch_stops_at_the_first_error() {
+        let options = options::Options { quiet: true, ..Default::default() };
+        let script = ["# a comment", "", "[Z] 1 + 1", "[Z] 1 / 0", "[Z] 2"];
+        assert_eq!(batch(lines(&script[..3]), "line", options.clone()), ExitCode::SUCCESS);
+        assert_eq!(batch(lines(&script), "line", options.clone()), ExitCode::FAILURE);
+
+        // the rest is run with keep_going, but the status is still a failure.
+        let keep_going = options::Options { keep_going: true, ..options.clone() };
+        assert_eq!(batch(lines(&script), "line", keep_going), ExitCode::FAILURE);
+        // nothing after exit is run.
+        assert_eq!(batch(lines(&["[Z] 1", "exit", "[Z] 1 / 0"]), "line", options), ExitCode::SUCCESS);
+    }
+
+    fn rendered(line: &str, options: &options::Options) -> String {
+        let mut session = session::Session::new();
+        let mut options = options.clone();
+        let outcome = run_line(&mut session, &mut options, line).unwrap();
+        render(outcome, &options)
+    }
+
+    #[test]
+    fn plain_and_quiet_output() {
+        let pretty = options::Options::default();
+        assert_eq!(rendered("[Z] add {1}{2}", &pretty), "Result $1: 3\n\n");
+        assert_eq!(rendered("vars", &pretty), "No variables.\n\n");
+
+        let quiet = options::Options { quiet: true, ..Default::default() };
+        assert_eq!(rendered("[Z] add {1}{2}", &quiet), "3\n");
+
+        // plain output has the values only, one per line, and no blank lines.
+        let plain = options::Options { format: options::Format::Plain, ..Default::default() };
+        assert_eq!(rendered("[Z] divmod {17}{5}", &plain), "3\n2\n");
+        assert_eq!(rendered("vars", &plain), "No variables.\n");
+        assert_eq!(rendered(":format plain [Z] 2^10", &pretty), "1024\n");
+
+        // in latex, the label is text, so that the whole output can be pasted in math mode.
+        let latex = options::Options { format: options::Format::Latex, ..Default::default() };
+        assert_eq!(rendered("[Z] add {1}{-2}", &latex), "\\text{Result \\$1: } 1 - 2 = -1 \\text{ in } \\mathbb{Z}\n\n");
+    }
+
+    #[test]
+    fn json_output() {
+        // one object per line: results, messages and errors alike.
+        let json = options::Options { format: options::Format::Json, ..Default::default() };
+        assert_eq!(rendered("[Z] divmod {17}{5}", &json), concat!(
+            r#"{"n": 1, "name": null, "algebra": "Z", "operation": "divmod", "operands": [{"text": "17"}, {"text": "5"}], "#,
+            r#""result": {"quotient": {"text": "3"}, "remainder": {"text": "2"}}, "context": null}"#, "\n"));
+        assert_eq!(rendered("vars", &json), "{\"message\": \"No variables.\"}\n");
+        assert_eq!(rendered("[Z] add {1}{2}", &json).lines().count(), 1);
+
+        let Err(err) = run_line(&mut session::Session::new(), &mut json.clone(), "[Z] 1/0") else {
+            panic!("1/0 is an error");
+        };
+        assert_eq!(error_json(&err, Some(("line", 4))), r#"{"error": "Zero cannot be a denominator.", "line": 4}"#);
+        assert_eq!(error_json(&err, None), r#"{"error": "Zero cannot be a denominator."}"#);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,130 @@
+// command line options. Commands come from -e, from a script, or from stdin when it
+// is not a terminal; the interactive REPL starts otherwise.
+#[derive(Debug, Default, Clone)]
+pub(super) struct Options {
+    pub script: Option<String>,
+    // commands given with -e, run in order in a single session.
+    pub commands: Vec<String>,
+    pub format: Format,
+    // no "Result $n:" labels, blank lines or greetings: only the output itself.
+    pub quiet: bool,
+    // in batch mode, go on after a failed line instead of stopping.
+    pub keep_going: bool,
+    pub help: bool,
+}
+
+// pretty is the output of the REPL. plain only prints the values of a result, one per
+// line: H, U, V for bezout, Q, R for divmod, X, M for crt, and the value otherwise.
+// latex prints the pretty summaries as LaTeX, to paste in math mode.
+// json prints one object per line, with the operands and the named components.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub(super) enum Format {
+    #[default]
+    Pretty,
+    Plain,
+    Latex,
+    Json,
+}
+
+impl Format {
+    pub fn from_name(input: &str) -> Option<Format> {
+        match input.to_lowercase().as_str() {
+            "pretty" => Some(Format::Pretty),
+            "plain" => Some(Format::Plain),
+            "latex" => Some(Format::Latex),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{self:?}").to_lowercase())
+    }
+}
+
+pub(super) const USAGE: &str = "\
+Usage: winkalc [OPTIONS] [SCRIPT]
+
+Without SCRIPT or -e, commands are read from stdin if it is not a terminal,
+and the interactive REPL starts otherwise.
+
+Options:
+  -e, --eval COMMAND     run COMMAND; may be repeated
+  -f, --format FORMAT    output format: pretty (default), plain, latex
+                         or json
+  -q, --quiet            print results only, without labels
+  -k, --keep-going       do not stop at the first failed line
+  -h, --help             print this help";
+
+pub(super) fn parse_args(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-e" | "--eval" => {
+                let Some(command) = args.next() else {
+                    return Err(format!("{arg} needs a command."));
+                };
+                options.commands.push(command);
+            }
+            "-f" | "--format" => {
+                let Some(format) = args.next() else {
+                    return Err(format!("{arg} needs a format."));
+                };
+                let Some(format) = Format::from_name(&format) else {
+                    return Err(format!("Unknown format {format}. Choose from: pretty, plain, latex, json."));
+                };
+                options.format = format;
+            }
+            "-q" | "--quiet" => options.quiet = true,
+            "-k" | "--keep-going" => options.keep_going = true,
+            "-h" | "--help" => options.help = true,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {arg}.")),
+            _ if options.script.is_some() => return Err("Only one script can be run at a time.".to_string()),
+            _ => options.script = Some(arg),
+        }
+    }
+
+    if options.script.is_some() && !options.commands.is_empty() {
+        return Err("Give either a script or commands with -e, not both.".to_string());
+    }
+
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn script_and_keep_going() {
+        let options = parse(&["-k", "script.txt"]).unwrap();
+        assert_eq!(options.script.as_deref(), Some("script.txt"));
+        assert!(options.keep_going && options.commands.is_empty());
+
+        let options = parse(&[]).unwrap();
+        assert!(options.script.is_none() && !options.keep_going);
+
+        assert!(parse(&["a.txt", "b.txt"]).is_err());
+        assert!(parse(&["--frobnicate"]).is_err());
+    }
+
+    #[test]
+    fn commands_and_formats() {
+        let options = parse(&["-q", "-e", "[Z] 1", "--format", "PLAIN", "--eval", "[Z] 2"]).unwrap();
+        assert_eq!(options.commands, vec!["[Z] 1".to_string(), "[Z] 2".to_string()]);
+        assert_eq!(options.format, Format::Plain);
+        assert!(options.quiet);
+
+        assert!(parse(&["-e"]).is_err());
+        assert!(parse(&["-f", "html"]).is_err());
+        assert!(parse(&["-e", "[Z] 1", "script.txt"]).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+use super::datatype::{Algebra, Operation, RawResult};
+use crate::arithmetic::GaloisField;
+use crate::customio::Scope;
+
+// variables bound with let, and the numbered results, kept for the whole REPL session.
+// A value belongs to the algebra it was computed in, and only commands in that algebra
+// can see it. Values are kept as displayed, which is a valid expression in their algebra.
+pub(super) struct Session {
+    vars: BTreeMap<String, (Algebra, String)>,
+    results: Vec<Entry>,
+    field: Option<(Algebra, GaloisField)>,
+}
+
+// result $n, with its named components, eg. U, V and H of a bezout.
+struct Entry {
+    alg: Algebra,
+    value: Option<String>,
+    components: Vec<(String, String)>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Session { vars: BTreeMap::new(), results: Vec::new(), field: None }
+    }
+
+    // numbers the result, starting from 1. The answer to a query is numbered as well,
+    // but it is no value: $n is left undefined.
+    pub fn record(&mut self, alg: &Algebra, op: Operation, res: &RawResult) -> usize {
+        let main = Some(res.main.text.clone());
+        let sub1 = res.subs.first().map(|x| x.text.clone());
+        let sub2 = res.subs.get(1).map(|x| x.text.clone());
+        let mut named: Vec<(String, Option<String>)> = match op {
+            Operation::Bezout | Operation::Trace if res.opers.len() == 2 => {
+                vec![("U".to_string(), sub1), ("V".to_string(), sub2)]
+            }
+            // U1, ..., Un for more operands.
+            Operation::Bezout => res.subs.iter().enumerate()
+                .map(|(k, x)| (format!("U{}", k + 1), Some(x.text.clone())))
+                .collect(),
+            Operation::DivMod => vec![("Q".to_string(), main.clone()), ("R".to_string(), sub1)],
+            Operation::Crt | Operation::Congruence => vec![("X".to_string(), main.clone()), ("M".to_string(), sub1)],
+            Operation::Solve | Operation::Pell => vec![("X".to_string(), main.clone()), ("Y".to_string(), sub1)],
+            _ => vec![],
+        };
+        if op == Operation::Bezout || op == Operation::Trace {
+            named.push(("H".to_string(), main.clone()));
+        }
+
+        self.results.push(Entry {
+            alg: alg.clone(),
+            value: if op.is_query() { None } else { main },
+            components: named.into_iter()
+                .filter_map(|(name, value)| Some((name, value?)))
+                .collect(),
+        });
+
+        self.results.len()
+    }
+
+    // rebinding a name replaces it, whatever its algebra was.
+    pub fn bind(&mut self, name: &str, alg: &Algebra, value: &str) {
+        self.vars.insert(name.to_string(), (alg.clone(), value.to_string()));
+    }
+
+    // the variables and results visible from alg. ans is the last result, if it lives in alg.
+    pub fn scope(&self, alg: &Algebra) -> Scope {
+        let mut scope: Scope = self.vars.iter()
+            .filter(|(_, (var_alg, _))| var_alg == alg)
+            .map(|(name, (_, value))| (name.clone(), value.clone()))
+            .collect();
+
+        for (i, entry) in self.results.iter().enumerate() {
+            if entry.alg != *alg {
+                continue;
+            }
+            let n = i + 1;
+            if let Some(value) = &entry.value {
+                scope.insert(format!("${n}"), value.clone());
+            }
+            for (name, value) in &entry.components {
+                scope.insert(format!("${n}.{name}"), value.clone());
+            }
+        }
+
+        if let Some(value) = self.results.last().filter(|entry| entry.alg == *alg)
+            .and_then(|entry| entry.value.as_ref()) {
+            scope.insert("ans".to_string(), value.clone());
+        }
+
+        scope
+    }
+
+    // the field of the last GF(p^n) algebra used, if alg is that one.
+    pub fn field(&self, alg: &Algebra) -> Option<GaloisField> {
+        self.field.as_ref().filter(|(field_alg, _)| field_alg == alg).map(|(_, field)| field.clone())
+    }
+
+    // only one field is kept: switching to another GF(p^n) replaces it.
+    pub fn keep_field(&mut self, alg: &Algebra, field: &GaloisField) {
+        self.field = Some((alg.clone(), field.clone()));
+    }
+
+    // false if there was no such variable.
+    pub fn unbind(&mut self, name: &str) -> bool {
+        self.vars.remove(name).is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.vars.clear();
+    }
+
+    // one line per variable, eg. "f = x^4 - 1 in [QPol]".
+    pub fn list(&self) -> String {
+        if self.vars.is_empty() {
+            return "No variables.".to_string();
+        }
+
+        let lines: Vec<String> = self.vars.iter()
+            .map(|(name, (alg, value))| format!("{name} = {value} in [{alg}]"))
+            .collect();
+
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::func::{execute, raw_parse};
+    use crate::arithmetic::{BigInt, Integer, Prime};
+    use crate::customio::Parse;
+
+    #[test]
+    fn variables_live_in_their_algebra() {
+        let mut session = Session::new();
+        assert_eq!(session.list(), "No variables.");
+
+        session.bind("f", &Algebra::QPol, "x^2 - 1");
+        session.bind("n", &Algebra::Z, "12");
+        assert_eq!(session.scope(&Algebra::QPol).get("f").map(String::as_str), Some("x^2 - 1"));
+        assert!(!session.scope(&Algebra::Q).contains_key("f"));
+        assert_eq!(session.list(), "f = x^2 - 1 in [QPol]\nn = 12 in [Z]");
+
+        // rebinding moves the name to the new algebra.
+        session.bind("f", &Algebra::Z, "7");
+        assert!(!session.scope(&Algebra::QPol).contains_key("f"));
+        assert_eq!(session.scope(&Algebra::Z).get("f").map(String::as_str), Some("7"));
+
+        assert!(session.unbind("f"));
+        assert!(!session.unbind("f"));
+        session.clear();
+        assert_eq!(session.list(), "No variables.");
+    }
+
+    fn record(session: &mut Session, line: &str) -> usize {
+        let cmd = raw_parse(line).unwrap();
+        let res = execute(&cmd).unwrap();
+        session.record(&cmd.alg, cmd.op, &res)
+    }
+
+    #[test]
+    fn results_and_their_components() {
+        let mut session = Session::new();
+        assert_eq!(record(&mut session, "[Z] bezout {4}{6}"), 1);
+        assert_eq!(record(&mut session, "[Z] divmod {7}{2}"), 2);
+
+        let scope = session.scope(&Algebra::Z);
+        let get = |name: &str| scope.get(name).map(String::as_str);
+        assert_eq!((get("$1"), get("$1.U"), get("$1.V"), get("$1.H")), (Some("2"), Some("-1"), Some("1"), Some("2")));
+        assert_eq!((get("$2.Q"), get("$2.R"), get("ans")), (Some("3"), Some("1"), Some("3")));
+        assert_eq!(Integer::parse_in("$1.u + $2.r * ans", &scope), Ok(Integer::new(2)));
+
+        // results stay in their algebra, and ans is the last result only if it is there.
+        assert_eq!(record(&mut session, "[Q] 1/2"), 3);
+        assert!(!session.scope(&Algebra::Z).contains_key("ans"));
+        assert!(!session.scope(&Algebra::Q).contains_key("$1"));
+
+        // a query is numbered, but it is no value.
+        assert_eq!(record(&mut session, "[Q] 3/4"), 4);
+        assert_eq!(record(&mut session, "[Z] isprime {7}"), 5);
+        let scope = session.scope(&Algebra::Z);
+        assert!(!scope.contains_key("$5") && !scope.contains_key("ans"));
+        assert_eq!(session.scope(&Algebra::Q).get("$4").map(String::as_str), Some("3/4"));
+    }
+
+    #[test]
+    fn keeps_the_last_field() {
+        let mut session = Session::new();
+        let gf8 = Algebra::GF(BigInt::from(2), 3, None);
+        let gf9 = Algebra::GF(BigInt::from(3), 2, None);
+        assert!(session.field(&gf8).is_none());
+
+        let field = GaloisField::with_degree(&Prime::try_new(2).unwrap(), 3);
+        session.keep_field(&gf8, &field);
+        assert_eq!(session.field(&gf8).unwrap().modulus, field.modulus);
+        assert!(session.field(&gf9).is_none());
+
+        session.keep_field(&gf9, &GaloisField::with_degree(&Prime::try_new(3).unwrap(), 2));
+        assert!(session.field(&gf8).is_none());
+    }
+}
@@ -1,5 +1,5 @@
 use winkalc::executable::go;
 
-fn main(){
-    go();
-}
\ No newline at end of file
+fn main() -> std::process::ExitCode {
+    go()
+}